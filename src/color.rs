@@ -0,0 +1,20 @@
+// File: src\color.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: ANSI color codes shared by the renderers.
+// License: MIT
+
+// ANSI Color Codes with True Color (24-bit)
+pub(crate) const COLOR_RESET: &str = "\x1b[0m";
+pub(crate) const COLOR_WHITE_ON_RED: &str = "\x1b[1;97;41m";
+pub(crate) const COLOR_ORANGE: &str = "\x1b[38;5;214m";
+pub(crate) const COLOR_RED: &str = "\x1b[38;2;255;85;85m"; // untracked entries
+pub(crate) const COLOR_DIM_STRIKE: &str = "\x1b[2;9m"; // --show-ignored entries
+
+// True Color (24-bit) ANSI codes - lighter color
+pub(crate) const COLOR_BRIGHT_YELLOW: &str = "\x1b[38;2;255;255;0m"; // #FFFF00
+pub(crate) const COLOR_BRIGHT_CYAN: &str = "\x1b[38;2;0;255;255m"; // #00FFFF
+pub(crate) const COLOR_LIGHT_MAGENTA_TRUE: &str = "\x1b[38;2;255;128;255m"; // Light magenta
+pub(crate) const COLOR_BRIGHT_GREEN: &str = "\x1b[38;2;0;255;128m"; // #00FF80 for symlinks
+pub(crate) const COLOR_GRAY: &str = "\x1b[38;2;160;160;160m"; // #A0A0A0 for meta info
+pub(crate) const COLOR_BRIGHT_WHITE: &str = "\x1b[38;2;230;230;230m"; // #E6E6E6 for summary
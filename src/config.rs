@@ -0,0 +1,164 @@
+// File: src\config.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Resolved traversal settings: ignore file names, compiled
+//              patterns and the root device.
+// License: MIT
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::ignore::ignore_file_names;
+#[cfg(unix)]
+use crate::meta::unix_dev;
+use crate::pattern::{Pattern, WildPattern};
+use crate::TreeConfig;
+
+// ── Config (shared traversal settings) ───────────────────────────────────────
+
+// ignore_case is stored for completeness; it is already baked into
+// WildPattern at construction time.
+#[allow(dead_code)]
+pub(crate) struct Config {
+    /// The canonical root of the walk.
+    pub(crate) root: PathBuf,
+    pub(crate) excludes: HashSet<String>,
+    pub(crate) default_excludes: Vec<String>,
+    pub(crate) show_all: bool,
+    pub(crate) show_ignored: bool,
+    pub(crate) git_status: bool,
+    pub(crate) git_tracked: bool,
+    pub(crate) git_untracked: bool,
+    pub(crate) rev: Option<String>,
+    /// Threads reading directories; 1 reads serially.
+    pub(crate) threads: usize,
+    /// Ignore file names looked for in every directory of the walk.
+    pub(crate) ignore_names: Vec<String>,
+    pub(crate) git_excludes: bool,
+    pub(crate) exception_patterns: Vec<Pattern>,
+    pub(crate) dirs_only: bool,
+    pub(crate) full_path: bool,
+    pub(crate) follow_links: bool,
+    pub(crate) level: Option<usize>,
+    pub(crate) pattern: Option<WildPattern>,
+    pub(crate) ignore_pattern: Option<WildPattern>,
+    pub(crate) ignore_case: bool,
+    pub(crate) dirsfirst: bool,
+    pub(crate) sort_time: bool,
+    pub(crate) sort_size: bool,
+    pub(crate) reverse: bool,
+    pub(crate) protections: bool,
+    pub(crate) owner: bool,
+    pub(crate) group: bool,
+    pub(crate) numeric_uid_gid: bool,
+    pub(crate) size_bytes: bool,
+    pub(crate) human_readable: bool,
+    pub(crate) si_units: bool,
+    pub(crate) date: bool,
+    pub(crate) classify: bool,
+    pub(crate) filelimit: Option<usize>,
+    pub(crate) prune: bool,
+    pub(crate) du: bool,
+    pub(crate) du_all: bool,
+    pub(crate) quote_chars: bool,
+    pub(crate) quote: bool,
+    pub(crate) xdev: bool,
+    pub(crate) inodes: bool,
+    pub(crate) device: bool,
+    pub(crate) root_dev: Option<u64>,
+    pub(crate) why: bool,
+}
+
+impl Config {
+    /// Resolve a [`TreeConfig`] against the canonical root path: compiles
+    /// the patterns and records the root device.
+    pub(crate) fn resolve(tc: &TreeConfig, abs_path: &Path) -> Config {
+        let mut exception_patterns = Vec::new();
+        for exc in &tc.exceptions {
+            match Pattern::from_string(exc) {
+                Ok(p) => exception_patterns.push(p),
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+
+        let pattern = tc
+            .pattern
+            .as_deref()
+            .map(|s| WildPattern::new(s, tc.ignore_case));
+        let ignore_pattern = tc
+            .ignore_pattern
+            .as_deref()
+            .map(|s| WildPattern::new(s, tc.ignore_case));
+
+        // root device id for --xdev (Unix only, None on Windows)
+        let root_dev: Option<u64> = {
+            #[cfg(unix)]
+            {
+                if tc.xdev {
+                    std::fs::metadata(abs_path).ok().map(|m| unix_dev(&m))
+                } else {
+                    None
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                None
+            }
+        };
+
+        Config {
+            root: abs_path.to_path_buf(),
+            excludes: tc.excludes.iter().cloned().collect(),
+            default_excludes: tc.default_excludes.clone(),
+            show_all: tc.show_all,
+            show_ignored: tc.show_ignored,
+            git_status: tc.git_status,
+            git_tracked: tc.git_tracked,
+            git_untracked: tc.git_untracked,
+            rev: tc.rev.clone(),
+            threads: match tc.threads {
+                0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+                n => n,
+            },
+            ignore_names: ignore_file_names(&tc.ignore_files),
+            git_excludes: !tc.no_git_excludes,
+            exception_patterns,
+            dirs_only: tc.dirs_only,
+            full_path: tc.full_path,
+            follow_links: tc.follow_links,
+            level: tc.max_depth,
+            pattern,
+            ignore_pattern,
+            ignore_case: tc.ignore_case,
+            dirsfirst: tc.dirsfirst,
+            sort_time: tc.sort_time,
+            sort_size: tc.sort_size,
+            reverse: tc.reverse,
+            protections: tc.protections,
+            owner: tc.owner,
+            group: tc.group,
+            numeric_uid_gid: tc.numeric_uid_gid,
+            size_bytes: tc.size_bytes,
+            human_readable: tc.human_readable,
+            si_units: tc.si_units,
+            date: tc.date,
+            classify: tc.classify,
+            filelimit: tc.filelimit,
+            prune: tc.prune,
+            du: tc.du,
+            du_all: tc.du && tc.du_all,
+            quote_chars: tc.quote_chars,
+            quote: tc.quote,
+            xdev: tc.xdev,
+            inodes: tc.inodes,
+            device: tc.device,
+            root_dev,
+            why: tc.why,
+        }
+    }
+
+    /// `path` relative to the root of the walk.
+    pub(crate) fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+}
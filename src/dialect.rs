@@ -0,0 +1,898 @@
+// File: src\dialect.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Ignore file dialects. Every known ignore file is parsed the way
+//              its own tool reads it (.hgignore, .dockerignore, .helmignore,
+//              .gcloudignore, gitignore for the rest) behind one matcher trait.
+// License: MIT
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::gitignore::{relative_slash_path, Gitignore};
+
+// ── Matcher trait ─────────────────────────────────────────────────────────────
+
+/// The rules of one ignore file, whatever its syntax.
+pub(crate) trait IgnoreMatcher: fmt::Debug + Send + Sync {
+    /// File name the rules come from (`.gitignore`, `.pt`, ...). Files with
+    /// the same name in nested directories stack on each other.
+    fn name(&self) -> &str;
+
+    /// The rule deciding about `path`: one that ignores it or one that
+    /// explicitly keeps it (such as a gitignore `!pattern`). `None` when no
+    /// rule applies.
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<RuleMatch<'_>>;
+}
+
+/// The rule an [`IgnoreMatcher`] decided with, and where it was written.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RuleMatch<'a> {
+    /// `false` when the rule keeps the entry.
+    pub(crate) ignored: bool,
+    pub(crate) source: &'a Path,
+    /// 1-based line number in `source`.
+    pub(crate) line: usize,
+    /// The line as written.
+    pub(crate) text: &'a str,
+}
+
+impl IgnoreMatcher for Gitignore {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<RuleMatch<'_>> {
+        self.matched(path, is_dir).map(|rule| RuleMatch {
+            ignored: !rule.negated,
+            source: &self.source,
+            line: rule.line,
+            text: &rule.text,
+        })
+    }
+}
+
+/// Read the ignore file `filename` in `dir` with the parser of its dialect.
+/// Unknown names (`.pt`, custom `-i` files) use gitignore syntax.
+pub(crate) fn load_matcher(dir: &Path, filename: &str) -> Option<Box<dyn IgnoreMatcher>> {
+    let path = dir.join(filename);
+    let content = fs::read_to_string(&path).ok()?;
+    let warn = |line: usize, msg: String| {
+        eprintln!("Warning: {}:{}: {}", path.display(), line, msg);
+    };
+    let matcher: Box<dyn IgnoreMatcher> = match filename {
+        ".hgignore" => Box::new(Hgignore::parse(&content, dir, &path, warn)),
+        ".dockerignore" => Box::new(Dockerignore::parse(&content, dir, &path, warn)),
+        ".helmignore" => Box::new(Helmignore::parse(&content, dir, &path, warn)),
+        ".gcloudignore" => Box::new(Gcloudignore::parse(&content, dir, &path)),
+        _ => Box::new(Gitignore::parse(&content, filename, dir, &path)),
+    };
+    Some(matcher)
+}
+
+// ── .hgignore ─────────────────────────────────────────────────────────────────
+
+/// Mercurial: regular expressions by default, switched with `syntax: glob`
+/// or `syntax: regexp` lines and overridden per line with `glob:`, `re:`,
+/// `rootglob:` or `path:` prefixes. Patterns are not rooted unless they say
+/// so, and there is no negation.
+#[derive(Debug)]
+struct Hgignore {
+    base: PathBuf,
+    source: PathBuf,
+    rules: Vec<HgRule>,
+}
+
+#[derive(Debug)]
+struct HgRule {
+    line: usize,
+    text: String,
+    regex: Regex,
+}
+
+#[derive(Clone, Copy)]
+enum HgSyntax {
+    Regexp,
+    Glob,
+    RootGlob,
+    Path,
+}
+
+impl HgSyntax {
+    fn from_name(name: &str) -> Option<HgSyntax> {
+        match name {
+            "re" | "regexp" | "relre" => Some(HgSyntax::Regexp),
+            "glob" | "relglob" => Some(HgSyntax::Glob),
+            "rootglob" => Some(HgSyntax::RootGlob),
+            "path" | "relpath" => Some(HgSyntax::Path),
+            _ => None,
+        }
+    }
+
+    /// The regex matching a `/`-separated path relative to the repository.
+    fn to_regex(self, pattern: &str) -> String {
+        match self {
+            HgSyntax::Regexp => pattern.to_string(),
+            HgSyntax::Glob => format!("^(?:|.*/){}(?:/|$)", hg_glob_regex(pattern)),
+            HgSyntax::RootGlob => format!("^{}(?:/|$)", hg_glob_regex(pattern)),
+            HgSyntax::Path if pattern == "." => String::new(),
+            HgSyntax::Path => format!("^{}(?:/|$)", regex::escape(pattern)),
+        }
+    }
+}
+
+impl Hgignore {
+    fn parse(content: &str, base: &Path, source: &Path, warn: impl Fn(usize, String)) -> Hgignore {
+        let mut syntax = HgSyntax::Regexp;
+        let mut rules = Vec::new();
+        for (idx, raw) in content.lines().enumerate() {
+            let line = strip_hg_comment(raw);
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("syntax:") {
+                match HgSyntax::from_name(name.trim()) {
+                    Some(s) => syntax = s,
+                    None => warn(idx + 1, format!("unknown syntax '{}'", name.trim())),
+                }
+                continue;
+            }
+            let (kind, pattern) = match line.split_once(':') {
+                Some((prefix, rest)) => match HgSyntax::from_name(prefix) {
+                    Some(kind) => (kind, rest),
+                    None => (syntax, line),
+                },
+                None => (syntax, line),
+            };
+            match Regex::new(&kind.to_regex(pattern)) {
+                Ok(regex) => rules.push(HgRule {
+                    line: idx + 1,
+                    text: line.to_string(),
+                    regex,
+                }),
+                Err(e) => warn(idx + 1, format!("invalid pattern '{}': {}", pattern, e)),
+            }
+        }
+        Hgignore {
+            base: base.to_path_buf(),
+            source: source.to_path_buf(),
+            rules,
+        }
+    }
+}
+
+impl IgnoreMatcher for Hgignore {
+    fn name(&self) -> &str {
+        ".hgignore"
+    }
+
+    fn decide(&self, path: &Path, _is_dir: bool) -> Option<RuleMatch<'_>> {
+        let rel = relative_slash_path(&self.base, path)?;
+        let rule = self.rules.iter().find(|r| r.regex.is_match(&rel))?;
+        Some(RuleMatch {
+            ignored: true,
+            source: &self.source,
+            line: rule.line,
+            text: &rule.text,
+        })
+    }
+}
+
+/// Everything from an unescaped `#` on is a comment; `\#` is a literal `#`.
+fn strip_hg_comment(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'#') => {
+                out.push('#');
+                chars.next();
+            }
+            '#' => break,
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Mercurial's glob translation: `*` stays within a directory, `**` spans
+/// directories, `?` is any character, `{a,b}` alternates and `[!...]`
+/// negates a class.
+fn hg_glob_regex(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::new();
+    let mut groups = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '*' if chars.get(i) == Some(&'*') => {
+                i += 1;
+                if chars.get(i) == Some(&'/') {
+                    i += 1;
+                    out.push_str("(?:.*/)?");
+                } else {
+                    out.push_str(".*");
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push('.'),
+            '[' => {
+                let mut j = i;
+                if matches!(chars.get(j), Some('!') | Some(']')) {
+                    j += 1;
+                }
+                while j < chars.len() && chars[j] != ']' {
+                    j += 1;
+                }
+                if j >= chars.len() {
+                    out.push_str("\\[");
+                    continue;
+                }
+                let stuff: String = chars[i..j].iter().collect();
+                let stuff = stuff.replace('\\', "\\\\");
+                i = j + 1;
+                out.push('[');
+                if let Some(rest) = stuff.strip_prefix('!') {
+                    out.push('^');
+                    out.push_str(rest);
+                } else if stuff.starts_with('^') {
+                    out.push('\\');
+                    out.push_str(&stuff);
+                } else {
+                    out.push_str(&stuff);
+                }
+                out.push(']');
+            }
+            '{' => {
+                groups += 1;
+                out.push_str("(?:");
+            }
+            '}' if groups > 0 => {
+                groups -= 1;
+                out.push(')');
+            }
+            ',' if groups > 0 => out.push('|'),
+            '\\' => match chars.get(i) {
+                Some(&next) => {
+                    i += 1;
+                    out.push_str(&regex::escape(&next.to_string()));
+                }
+                None => out.push_str("\\\\"),
+            },
+            _ => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    out
+}
+
+// ── .dockerignore ─────────────────────────────────────────────────────────────
+
+/// Docker: Go `filepath.Match` patterns plus `**`, relative to the build
+/// context root (a leading `/` changes nothing). A pattern also matches
+/// everything below the directories it matches; the last matching line
+/// wins, with `!` lines re-including.
+#[derive(Debug)]
+struct Dockerignore {
+    base: PathBuf,
+    source: PathBuf,
+    rules: Vec<DockerRule>,
+}
+
+#[derive(Debug)]
+struct DockerRule {
+    negated: bool,
+    line: usize,
+    text: String,
+    regex: Regex,
+    /// The components of the pattern up to the first `**`, each on its
+    /// own, for telling whether a `!` line reaches into a directory.
+    leading: Vec<Regex>,
+    /// Whether a `**` component follows `leading`.
+    deep: bool,
+}
+
+impl DockerRule {
+    /// Whether the rule can match something below the directory `dir`
+    /// (`/`-separated, relative to the context). Like moby's
+    /// `MatchesOrParentMatches`, `dir` has to match the leading components
+    /// of the pattern one by one, and the pattern must go deeper than `dir`
+    /// or reach a `**` first.
+    fn reaches_into(&self, dir: &str) -> bool {
+        let dirs: Vec<&str> = dir.split('/').collect();
+        (self.deep || self.leading.len() > dirs.len())
+            && self
+                .leading
+                .iter()
+                .zip(&dirs)
+                .all(|(re, name)| re.is_match(name))
+    }
+}
+
+impl Dockerignore {
+    fn parse(
+        content: &str,
+        base: &Path,
+        source: &Path,
+        warn: impl Fn(usize, String),
+    ) -> Dockerignore {
+        let mut rules = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut pattern = line.trim();
+            if pattern.is_empty() {
+                continue;
+            }
+            let negated = pattern.starts_with('!');
+            if negated {
+                pattern = pattern[1..].trim();
+            }
+            let pattern = clean_path(pattern);
+            let components: Vec<&str> = pattern.split('/').collect();
+            let deep = components.iter().position(|c| c.contains("**"));
+            let leading = components[..deep.unwrap_or(components.len())]
+                .iter()
+                .map(|c| Regex::new(&docker_regex(c)))
+                .collect::<Result<Vec<_>, _>>();
+            match (Regex::new(&docker_regex(&pattern)), leading) {
+                (Ok(regex), Ok(leading)) => rules.push(DockerRule {
+                    negated,
+                    line: idx + 1,
+                    text: line.trim().to_string(),
+                    regex,
+                    leading,
+                    deep: deep.is_some(),
+                }),
+                (Err(e), _) | (_, Err(e)) => {
+                    warn(idx + 1, format!("invalid pattern '{}': {}", pattern, e))
+                }
+            }
+        }
+        Dockerignore {
+            base: base.to_path_buf(),
+            source: source.to_path_buf(),
+            rules,
+        }
+    }
+}
+
+impl IgnoreMatcher for Dockerignore {
+    fn name(&self) -> &str {
+        ".dockerignore"
+    }
+
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<RuleMatch<'_>> {
+        let rel = relative_slash_path(&self.base, path)?;
+        let parents: Vec<&str> = rel.split('/').collect();
+        let rule = self.rules.iter().rev().find(|r| {
+            r.regex.is_match(&rel)
+                || (1..parents.len()).any(|n| r.regex.is_match(&parents[..n].join("/")))
+        })?;
+        if !rule.negated && is_dir {
+            // Like docker's context walk: an excluded directory is still
+            // entered when a `!` line may match something inside it.
+            if self.rules.iter().any(|r| r.negated && r.reaches_into(&rel)) {
+                return None;
+            }
+        }
+        Some(RuleMatch {
+            ignored: !rule.negated,
+            source: &self.source,
+            line: rule.line,
+            text: &rule.text,
+        })
+    }
+}
+
+/// Go's `filepath.Clean` on a `/`-separated pattern, without the leading
+/// `/`: drops `.` and empty components and resolves `..`.
+fn clean_path(pattern: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in pattern.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.last().is_some_and(|p| *p != "..") {
+                    parts.pop();
+                } else if !pattern.starts_with('/') {
+                    parts.push(part);
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// The regex docker compiles a pattern to. Only `.` and `$` are escaped,
+/// so other regex syntax in a pattern keeps its regex meaning, as it does
+/// in docker.
+fn docker_regex(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::from("^");
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '*' if chars.get(i) == Some(&'*') => {
+                i += 1;
+                if chars.get(i) == Some(&'/') {
+                    i += 1;
+                }
+                if i == chars.len() {
+                    out.push_str(".*");
+                } else {
+                    out.push_str("(.*/)?");
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '.' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => {
+                out.push('\\');
+                if let Some(&next) = chars.get(i) {
+                    i += 1;
+                    out.push(next);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('$');
+    out
+}
+
+// ── .helmignore ───────────────────────────────────────────────────────────────
+
+/// Helm: Go `filepath.Match` globs without `**`. A pattern without `/`
+/// matches the base name, others the path from the chart root. Rules are
+/// checked in order and the first match ignores; a `!pattern` line ignores
+/// everything it does *not* match, which is how helm reads negation.
+#[derive(Debug)]
+struct Helmignore {
+    base: PathBuf,
+    source: PathBuf,
+    rules: Vec<HelmRule>,
+}
+
+#[derive(Debug)]
+struct HelmRule {
+    negated: bool,
+    line: usize,
+    text: String,
+    dir_only: bool,
+    /// Matched against the base name instead of the relative path.
+    basename: bool,
+    regex: Regex,
+}
+
+impl Helmignore {
+    fn parse(
+        content: &str,
+        base: &Path,
+        source: &Path,
+        warn: impl Fn(usize, String),
+    ) -> Helmignore {
+        let mut rules = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            let text = line.trim();
+            let mut rule = text;
+            if rule.is_empty() || rule.starts_with('#') {
+                continue;
+            }
+            if rule.contains("**") {
+                warn(idx + 1, "double-star (**) syntax is not supported".into());
+                continue;
+            }
+            let negated = rule.starts_with('!');
+            if negated {
+                rule = &rule[1..];
+            }
+            let dir_only = rule.ends_with('/');
+            let rule = rule.trim_end_matches('/');
+            let basename = !rule.contains('/');
+            let rule = rule.strip_prefix('/').unwrap_or(rule);
+            match go_match_regex(rule).and_then(|re| Regex::new(&re).ok()) {
+                Some(regex) => rules.push(HelmRule {
+                    negated,
+                    line: idx + 1,
+                    text: text.to_string(),
+                    dir_only,
+                    basename,
+                    regex,
+                }),
+                None => warn(idx + 1, format!("invalid pattern '{}'", rule)),
+            }
+        }
+        Helmignore {
+            base: base.to_path_buf(),
+            source: source.to_path_buf(),
+            rules,
+        }
+    }
+}
+
+impl IgnoreMatcher for Helmignore {
+    fn name(&self) -> &str {
+        ".helmignore"
+    }
+
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<RuleMatch<'_>> {
+        let rel = relative_slash_path(&self.base, path)?;
+        let name = rel.rsplit('/').next().unwrap_or(&rel);
+        let rule = self.rules.iter().find(|rule| {
+            let text = if rule.basename { name } else { rel.as_str() };
+            if rule.negated {
+                (rule.dir_only && !is_dir) || !rule.regex.is_match(text)
+            } else {
+                (is_dir || !rule.dir_only) && rule.regex.is_match(text)
+            }
+        })?;
+        Some(RuleMatch {
+            ignored: true,
+            source: &self.source,
+            line: rule.line,
+            text: &rule.text,
+        })
+    }
+}
+
+/// Go's `filepath.Match` pattern as an anchored regex: `*` and `?` stay
+/// within a directory, `[^...]` negates a class and `\` escapes. `None`
+/// for a malformed pattern.
+fn go_match_regex(pattern: &str) -> Option<String> {
+    let mut out = String::from("^");
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '\\' => out.push_str(&regex::escape(&chars.next()?.to_string())),
+            '[' => {
+                out.push('[');
+                let mut first = true;
+                loop {
+                    let mut c = chars.next()?;
+                    if c == ']' && !first {
+                        break;
+                    }
+                    if c == '^' && first {
+                        out.push('^');
+                        first = false;
+                        continue;
+                    }
+                    first = false;
+                    if c == '\\' {
+                        c = chars.next()?;
+                    }
+                    if c == '-' {
+                        out.push('-');
+                    } else {
+                        out.push_str(&regex::escape(&c.to_string()));
+                    }
+                }
+                out.push(']');
+            }
+            _ => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    out.push('$');
+    Some(out)
+}
+
+// ── .gcloudignore ─────────────────────────────────────────────────────────────
+
+/// gcloud: gitignore syntax plus `#!include:FILE` lines, which splice in the
+/// patterns of another file next to the `.gcloudignore` (usually
+/// `#!include:.gitignore`). Each run of lines keeps its own file and line
+/// numbers; the last matching rule over all of them wins.
+#[derive(Debug)]
+struct Gcloudignore {
+    parts: Vec<Gitignore>,
+}
+
+impl Gcloudignore {
+    fn parse(content: &str, dir: &Path, source: &Path) -> Gcloudignore {
+        let name = ".gcloudignore";
+        let mut parts = Vec::new();
+        let mut run: Vec<(usize, &str)> = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            let Some(file) = line.trim().strip_prefix("#!include:") else {
+                run.push((idx, line));
+                continue;
+            };
+            parts.push(Gitignore::from_lines(run.drain(..), name, dir, source));
+            let included = dir.join(file.trim());
+            if let Ok(text) = fs::read_to_string(&included) {
+                parts.push(Gitignore::parse(&text, name, dir, &included));
+            }
+        }
+        parts.push(Gitignore::from_lines(run, name, dir, source));
+        Gcloudignore { parts }
+    }
+}
+
+impl IgnoreMatcher for Gcloudignore {
+    fn name(&self) -> &str {
+        ".gcloudignore"
+    }
+
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<RuleMatch<'_>> {
+        self.parts.iter().rev().find_map(|p| p.decide(path, is_dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "/ctx";
+
+    /// Whether `matcher` ignores `path` (relative to the base); `None` when
+    /// no rule decides.
+    fn ignored(matcher: &dyn IgnoreMatcher, path: &str, is_dir: bool) -> Option<bool> {
+        matcher
+            .decide(&Path::new(BASE).join(path), is_dir)
+            .map(|m| m.ignored)
+    }
+
+    fn no_warnings(line: usize, msg: String) {
+        panic!("unexpected warning on line {}: {}", line, msg);
+    }
+
+    mod hgignore {
+        use super::*;
+
+        fn hg(content: &str) -> Hgignore {
+            let source = Path::new(BASE).join(".hgignore");
+            Hgignore::parse(content, Path::new(BASE), &source, no_warnings)
+        }
+
+        #[test]
+        fn regexp_is_the_default_and_unanchored() {
+            let m = hg("\\.o$\n");
+            assert_eq!(ignored(&m, "main.o", false), Some(true));
+            assert_eq!(ignored(&m, "src/lib/main.o", false), Some(true));
+            assert_eq!(ignored(&m, "main.odt", false), None);
+        }
+
+        #[test]
+        fn syntax_lines_switch_the_default() {
+            let m = hg("syntax: glob\n*.pyc\nsyntax: regexp\n^out$\n");
+            assert_eq!(ignored(&m, "pkg/mod.pyc", false), Some(true));
+            assert_eq!(ignored(&m, "mod.py", false), None);
+            assert_eq!(ignored(&m, "out", true), Some(true));
+            assert_eq!(ignored(&m, "src/out", true), None);
+        }
+
+        #[test]
+        fn glob_prefix_matches_at_any_depth() {
+            let m = hg("glob:build\n");
+            assert_eq!(ignored(&m, "build", true), Some(true));
+            assert_eq!(ignored(&m, "a/build/x.o", false), Some(true));
+            assert_eq!(ignored(&m, "builder", true), None);
+        }
+
+        #[test]
+        fn re_prefix_overrides_glob_syntax() {
+            let m = hg("syntax: glob\nre:^tmp[0-9]+$\n");
+            assert_eq!(ignored(&m, "tmp42", false), Some(true));
+            assert_eq!(ignored(&m, "tmpx", false), None);
+        }
+
+        #[test]
+        fn rootglob_prefix_is_anchored() {
+            let m = hg("rootglob:*.txt\n");
+            assert_eq!(ignored(&m, "notes.txt", false), Some(true));
+            assert_eq!(ignored(&m, "docs/notes.txt", false), None);
+        }
+
+        #[test]
+        fn path_prefix_is_literal_and_rooted() {
+            let m = hg("path:docs/a*b\n");
+            assert_eq!(ignored(&m, "docs/a*b", false), Some(true));
+            assert_eq!(ignored(&m, "docs/a*b/c", false), Some(true));
+            assert_eq!(ignored(&m, "docs/axb", false), None);
+            assert_eq!(ignored(&m, "x/docs/a*b", false), None);
+        }
+
+        #[test]
+        fn comments_are_stripped_unless_escaped() {
+            let m = hg("syntax: glob\nlog  # the logs\n\\#draft\n");
+            assert_eq!(ignored(&m, "log", true), Some(true));
+            assert_eq!(ignored(&m, "#draft", false), Some(true));
+            assert_eq!(ignored(&m, "draft", false), None);
+        }
+    }
+
+    mod dockerignore {
+        use super::*;
+
+        fn docker(content: &str) -> Dockerignore {
+            let source = Path::new(BASE).join(".dockerignore");
+            Dockerignore::parse(content, Path::new(BASE), &source, no_warnings)
+        }
+
+        #[test]
+        fn pattern_matches_the_path_and_everything_below() {
+            let m = docker("build\n*.log\n");
+            assert_eq!(ignored(&m, "build", true), Some(true));
+            assert_eq!(ignored(&m, "build/out/a.o", false), Some(true));
+            assert_eq!(ignored(&m, "app.log", false), Some(true));
+            assert_eq!(ignored(&m, "src/app.log", false), None);
+            assert_eq!(ignored(&m, "src/main.rs", false), None);
+        }
+
+        #[test]
+        fn double_star_spans_directories() {
+            let m = docker("**/*.tmp\n");
+            assert_eq!(ignored(&m, "a.tmp", false), Some(true));
+            assert_eq!(ignored(&m, "a/b/c.tmp", false), Some(true));
+            assert_eq!(ignored(&m, "a/b/c.txt", false), None);
+        }
+
+        #[test]
+        fn leading_slash_and_dots_are_cleaned() {
+            let m = docker("/docs/./draft\n");
+            assert_eq!(ignored(&m, "docs/draft", true), Some(true));
+            assert_eq!(ignored(&m, "docs/final", true), None);
+        }
+
+        #[test]
+        fn negation_re_includes() {
+            let m = docker("*.md\n!README.md\n");
+            assert_eq!(ignored(&m, "CHANGES.md", false), Some(true));
+            assert_eq!(ignored(&m, "README.md", false), Some(false));
+        }
+
+        #[test]
+        fn negation_naming_a_path_reopens_the_directory() {
+            let m = docker("vendor\n!vendor/keep.txt\n");
+            assert_eq!(ignored(&m, "vendor", true), None);
+            assert_eq!(ignored(&m, "vendor/keep.txt", false), Some(false));
+            assert_eq!(ignored(&m, "vendor/other.txt", false), Some(true));
+        }
+
+        #[test]
+        fn negation_with_a_wildcard_reopens_the_directory() {
+            let m = docker("vendor\n!*/keep.txt\n");
+            assert_eq!(ignored(&m, "vendor", true), None);
+            assert_eq!(ignored(&m, "vendor/keep.txt", false), Some(false));
+            assert_eq!(ignored(&m, "vendor/other.txt", false), Some(true));
+            // `*/keep.txt` cannot match anything two levels down.
+            assert_eq!(ignored(&m, "vendor/lib", true), Some(true));
+        }
+
+        #[test]
+        fn negation_with_double_star_reopens_every_directory() {
+            let m = docker("vendor\n!**/keep.txt\n");
+            assert_eq!(ignored(&m, "vendor", true), None);
+            assert_eq!(ignored(&m, "vendor/lib", true), None);
+            assert_eq!(ignored(&m, "vendor/lib/keep.txt", false), Some(false));
+            assert_eq!(ignored(&m, "vendor/lib/other.txt", false), Some(true));
+        }
+
+        #[test]
+        fn negation_elsewhere_leaves_the_directory_excluded() {
+            let m = docker("vendor\n!docs/*/keep.txt\n");
+            assert_eq!(ignored(&m, "vendor", true), Some(true));
+        }
+    }
+
+    mod helmignore {
+        use super::*;
+
+        fn helm(content: &str) -> Helmignore {
+            let source = Path::new(BASE).join(".helmignore");
+            Helmignore::parse(content, Path::new(BASE), &source, no_warnings)
+        }
+
+        #[test]
+        fn pattern_without_slash_matches_the_base_name() {
+            let m = helm("*.swp\n");
+            assert_eq!(ignored(&m, "a.swp", false), Some(true));
+            assert_eq!(ignored(&m, "templates/b.swp", false), Some(true));
+            assert_eq!(ignored(&m, "b.swpx", false), None);
+        }
+
+        #[test]
+        fn pattern_with_slash_matches_the_path() {
+            let m = helm("/templates/*.bak\n");
+            assert_eq!(ignored(&m, "templates/a.bak", false), Some(true));
+            assert_eq!(ignored(&m, "other/templates/a.bak", false), None);
+        }
+
+        #[test]
+        fn trailing_slash_matches_directories_only() {
+            let m = helm("ci/\n");
+            assert_eq!(ignored(&m, "ci", true), Some(true));
+            assert_eq!(ignored(&m, "ci", false), None);
+        }
+
+        #[test]
+        fn negation_hides_everything_it_does_not_match() {
+            let m = helm("!*.yaml\n");
+            assert_eq!(ignored(&m, "values.yaml", false), None);
+            assert_eq!(ignored(&m, "README.md", false), Some(true));
+        }
+
+        #[test]
+        fn double_star_lines_are_skipped() {
+            let source = Path::new(BASE).join(".helmignore");
+            let warned = std::cell::Cell::new(0);
+            let m = Helmignore::parse("**/*.tmp\n", Path::new(BASE), &source, |_, _| {
+                warned.set(warned.get() + 1)
+            });
+            assert_eq!(warned.get(), 1);
+            assert_eq!(ignored(&m, "a/b.tmp", false), None);
+        }
+    }
+
+    mod gcloudignore {
+        use super::*;
+        use tempfile::TempDir;
+
+        fn decide(m: &Gcloudignore, dir: &TempDir, path: &str, is_dir: bool) -> Option<bool> {
+            m.decide(&dir.path().join(path), is_dir).map(|r| r.ignored)
+        }
+
+        #[test]
+        fn gitignore_syntax_with_negation() {
+            let dir = TempDir::new().unwrap();
+            let source = dir.path().join(".gcloudignore");
+            let m = Gcloudignore::parse("*.log\n!keep.log\nnode_modules/\n", dir.path(), &source);
+            assert_eq!(decide(&m, &dir, "app.log", false), Some(true));
+            assert_eq!(decide(&m, &dir, "keep.log", false), Some(false));
+            assert_eq!(decide(&m, &dir, "node_modules", true), Some(true));
+            assert_eq!(decide(&m, &dir, "node_modules", false), None);
+            assert_eq!(decide(&m, &dir, "main.py", false), None);
+        }
+
+        #[test]
+        fn include_splices_in_another_file() {
+            let dir = TempDir::new().unwrap();
+            fs::write(dir.path().join(".gitignore"), "*.pyc\n").unwrap();
+            let source = dir.path().join(".gcloudignore");
+            let m = Gcloudignore::parse("#!include:.gitignore\n.git\n", dir.path(), &source);
+            let rule = m.decide(&dir.path().join("x.pyc"), false).unwrap();
+            assert!(rule.ignored);
+            assert_eq!(rule.source, dir.path().join(".gitignore"));
+            assert_eq!(rule.line, 1);
+            assert_eq!(decide(&m, &dir, ".git", true), Some(true));
+            assert_eq!(decide(&m, &dir, "x.py", false), None);
+        }
+
+        #[test]
+        fn later_lines_win_over_included_ones() {
+            let dir = TempDir::new().unwrap();
+            fs::write(dir.path().join(".gitignore"), "*.pyc\n").unwrap();
+            let source = dir.path().join(".gcloudignore");
+            let m = Gcloudignore::parse("#!include:.gitignore\n!keep.pyc\n", dir.path(), &source);
+            assert_eq!(decide(&m, &dir, "keep.pyc", false), Some(false));
+            assert_eq!(decide(&m, &dir, "x.pyc", false), Some(true));
+        }
+
+        #[test]
+        fn missing_include_is_skipped() {
+            let dir = TempDir::new().unwrap();
+            let source = dir.path().join(".gcloudignore");
+            let m = Gcloudignore::parse("#!include:nope\n*.tmp\n", dir.path(), &source);
+            assert_eq!(decide(&m, &dir, "a.tmp", false), Some(true));
+        }
+    }
+}
@@ -0,0 +1,221 @@
+// File: src\explain.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: --explain: follows one path down from the root through the same
+//              filters as the walk and reports what each step decided.
+// License: MIT
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Component, Path};
+
+use crate::config::Config;
+use crate::ignore::{load_nested_ignore_files, load_root_ignore_files, IgnoreStack};
+use crate::model::{Explanation, HideReason};
+use crate::walk::check_entry;
+
+/// One explanation per component of `target` below the root, stopping at
+/// the first hidden one. `target` must be absolute and inside the root.
+pub(crate) fn explain_path(config: &Config, target: &Path) -> io::Result<Vec<Explanation>> {
+    let rel = target.strip_prefix(&config.root).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("not inside the tree root {}", config.root.display()),
+        )
+    })?;
+    let components: Vec<OsString> = rel
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_os_string()),
+            _ => None,
+        })
+        .collect();
+    let mut out = Vec::new();
+    let ignores = IgnoreStack::new(load_root_ignore_files(&config.root, config));
+    explain_in(&config.root, &components, 1, config, &ignores, &mut out)?;
+    Ok(out)
+}
+
+fn explain_in(
+    dir: &Path,
+    components: &[OsString],
+    depth: usize,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    out: &mut Vec<Explanation>,
+) -> io::Result<()> {
+    let Some((name, rest)) = components.split_first() else {
+        return Ok(());
+    };
+    let path = dir.join(name);
+    let meta = fs::symlink_metadata(&path)?;
+
+    // The walk stops before the entry filters at these two.
+    let too_deep = config
+        .level
+        .filter(|&max| depth > max)
+        .map(HideReason::Level);
+    let too_many = match config.filelimit {
+        Some(limit) => {
+            let entries = fs::read_dir(dir)?.count();
+            (entries > limit).then_some(HideReason::FileLimit { entries, limit })
+        }
+        None => None,
+    };
+    let explanation = match too_deep.or(too_many) {
+        Some(reason) => Explanation {
+            path: config.relative(&path),
+            hidden_by: Some(reason),
+            exception: None,
+        },
+        None => check_entry(&name.to_string_lossy(), &path, &meta, config, ignores),
+    };
+    let hidden = explanation.hidden_by.is_some();
+    out.push(explanation);
+    if hidden || !meta.is_dir() {
+        return Ok(());
+    }
+    let nested = ignores.push(load_nested_ignore_files(&path, &config.ignore_names));
+    explain_in(&path, rest, depth + 1, config, &nested, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use crate::TreeConfig;
+
+    /// `a.log` ignored by the top `.gitignore`, `src/gen/` by the one in
+    /// `src/`, and five files in `many/`.
+    fn fixture() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        fs::create_dir_all(root.join("src").join("gen")).unwrap();
+        fs::create_dir(root.join("many")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("a.log"), "").unwrap();
+        fs::write(root.join("src").join(".gitignore"), "# generated\ngen/\n").unwrap();
+        fs::write(root.join("src").join("main.rs"), "").unwrap();
+        fs::write(root.join("src").join("gen").join("x.rs"), "").unwrap();
+        for i in 0..5 {
+            fs::write(root.join("many").join(format!("f{}", i)), "").unwrap();
+        }
+        (dir, root)
+    }
+
+    fn explain(root: &Path, tc: TreeConfig, target: &str) -> Vec<Explanation> {
+        let config = Config::resolve(&tc, root);
+        explain_path(&config, &root.join(target)).unwrap()
+    }
+
+    /// The path and the reason of every step.
+    fn steps(explanations: &[Explanation]) -> Vec<(PathBuf, Option<HideReason>)> {
+        explanations
+            .iter()
+            .map(|e| (e.path.clone(), e.hidden_by.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn listed_path_has_one_step_per_component() {
+        let (_dir, root) = fixture();
+        let out = explain(&root, TreeConfig::default(), "src/main.rs");
+        assert_eq!(
+            steps(&out),
+            [
+                (PathBuf::from("src"), None),
+                (Path::new("src").join("main.rs"), None)
+            ]
+        );
+        assert!(explain(&root, TreeConfig::default(), "").is_empty());
+    }
+
+    #[test]
+    fn nested_ignore_file_hides_and_stops_the_path() {
+        let (_dir, root) = fixture();
+        let out = explain(&root, TreeConfig::default(), "src/gen/x.rs");
+        assert_eq!(
+            steps(&out),
+            [
+                (PathBuf::from("src"), None),
+                (
+                    Path::new("src").join("gen"),
+                    Some(HideReason::IgnoreFile {
+                        file: root.join("src").join(".gitignore"),
+                        line: 2,
+                        rule: "gen/".into(),
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn level_stops_before_the_filters() {
+        let (_dir, root) = fixture();
+        let tc = TreeConfig {
+            max_depth: Some(1),
+            ..TreeConfig::default()
+        };
+        let out = explain(&root, tc, "src/gen/x.rs");
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[1].hidden_by, Some(HideReason::Level(1)));
+    }
+
+    #[test]
+    fn filelimit_stops_before_the_filters() {
+        let (_dir, root) = fixture();
+        let tc = TreeConfig {
+            filelimit: Some(4),
+            ..TreeConfig::default()
+        };
+        // The root has four entries, `many/` five.
+        let out = explain(&root, tc, "many/f0");
+        assert_eq!(
+            steps(&out),
+            [
+                (PathBuf::from("many"), None),
+                (
+                    Path::new("many").join("f0"),
+                    Some(HideReason::FileLimit {
+                        entries: 5,
+                        limit: 4
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exception_is_reported_with_what_it_overrode() {
+        let (_dir, root) = fixture();
+        let tc = TreeConfig {
+            exceptions: vec!["*.log".into()],
+            ..TreeConfig::default()
+        };
+        let out = explain(&root, tc, "a.log");
+        assert_eq!(out[0].hidden_by, None);
+        let (pattern, overridden) = out[0].exception.clone().unwrap();
+        assert_eq!(pattern, "*.log");
+        assert!(matches!(overridden, HideReason::IgnoreFile { line: 1, .. }));
+    }
+
+    #[test]
+    fn target_outside_the_root_is_an_error() {
+        let (_dir, root) = fixture();
+        let config = Config::resolve(&TreeConfig::default(), &root.join("src"));
+        let err = explain_path(&config, &root.join("a.log")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(err.to_string().starts_with("not inside the tree root"));
+    }
+
+    #[test]
+    fn missing_entry_is_an_error() {
+        let (_dir, root) = fixture();
+        let config = Config::resolve(&TreeConfig::default(), &root);
+        let err = explain_path(&config, &root.join("src").join("nope.rs")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
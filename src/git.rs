@@ -0,0 +1,114 @@
+// File: src\git.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Just enough of git's layout to find the work tree around a
+//              path and read single values from the gitconfig files.
+// License: MIT
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ── Work tree ─────────────────────────────────────────────────────────────────
+
+/// A git work tree and the directory holding its metadata.
+#[derive(Debug, Clone)]
+pub(crate) struct Repo {
+    /// Top of the work tree, the directory containing `.git`.
+    pub(crate) root: PathBuf,
+    /// Where `info/` and `config` live: `.git`, the directory a `.git` file
+    /// points to (submodules), or the `commondir` of a linked worktree.
+    pub(crate) common_dir: PathBuf,
+}
+
+impl Repo {
+    /// The work tree `path` is in: the nearest ancestor (or `path` itself)
+    /// with a `.git` directory or `gitdir:` file.
+    pub(crate) fn discover(path: &Path) -> Option<Repo> {
+        path.ancestors().find_map(Repo::open)
+    }
+
+    fn open(root: &Path) -> Option<Repo> {
+        let dot_git = root.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            root.join(target)
+        };
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => git_dir.join(common.trim()),
+            Err(_) => git_dir,
+        };
+        Some(Repo {
+            root: root.to_path_buf(),
+            common_dir,
+        })
+    }
+
+    /// The value of `section.key` as git sees it: the system, global and
+    /// repository config files, `include` and `includeIf` directives
+    /// followed.
+    pub(crate) fn config_value(&self, section: &str, key: &str) -> Option<String> {
+        let config = match git2::Repository::open(&self.root) {
+            Ok(repo) => repo.config(),
+            Err(_) => git2::Config::open_default(),
+        };
+        config
+            .ok()?
+            .get_string(&format!("{}.{}", section, key))
+            .ok()
+    }
+}
+
+// ── Locations ─────────────────────────────────────────────────────────────────
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config` when it is unset.
+pub(crate) fn xdg_config_home() -> Option<PathBuf> {
+    match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => home_dir().map(|h| h.join(".config")),
+    }
+}
+
+/// `~/` at the start of a config path replaced by the home directory.
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_value_follows_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        fs::write(
+            dir.path().join("extra.gitconfig"),
+            "[core]\n\texcludesFile = ~/my-ignore\n",
+        )
+        .unwrap();
+        let config = dir.path().join(".git").join("config");
+        let mut content = fs::read_to_string(&config).unwrap();
+        content.push_str("[include]\n\tpath = ../extra.gitconfig\n");
+        fs::write(&config, content).unwrap();
+
+        let repo = Repo::discover(dir.path()).unwrap();
+        assert_eq!(
+            repo.config_value("core", "excludesFile").as_deref(),
+            Some("~/my-ignore")
+        );
+        assert_eq!(repo.config_value("core", "noSuchKey"), None);
+    }
+}
@@ -0,0 +1,486 @@
+// File: src\gitignore.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: gitignore-compatible rules: negation, anchoring, directory-only
+//              patterns, `**` and escapes, matched with git's wildmatch
+//              semantics.
+// License: MIT
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ── Rule ──────────────────────────────────────────────────────────────────────
+
+/// One pattern line of an ignore file.
+#[derive(Debug, Clone)]
+pub(crate) struct IgnoreRule {
+    /// `!pattern`: re-include what earlier rules excluded.
+    pub(crate) negated: bool,
+    /// 1-based line number in the ignore file.
+    pub(crate) line: usize,
+    /// The line as written, without trailing whitespace.
+    pub(crate) text: String,
+    /// `pattern/`: only matches directories.
+    dir_only: bool,
+    /// The pattern has a `/` before its end, so it is matched against the
+    /// path relative to the ignore file instead of the bare name.
+    anchored: bool,
+    glob: Vec<char>,
+}
+
+impl IgnoreRule {
+    /// Parse line `line_no`; `None` for blank lines and comments.
+    pub(crate) fn parse(line_no: usize, line: &str) -> Option<IgnoreRule> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let line = trim_unescaped_trailing_spaces(line);
+        let (negated, mut body) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let dir_only = body.ends_with('/') && !body.ends_with("\\/");
+        if dir_only {
+            body = body.trim_end_matches('/');
+        }
+        let anchored = body.contains('/');
+        let body = body.strip_prefix('/').unwrap_or(body);
+        if body.is_empty() {
+            return None;
+        }
+        Some(IgnoreRule {
+            negated,
+            line: line_no,
+            text: line.to_string(),
+            dir_only,
+            anchored,
+            glob: body.chars().collect(),
+        })
+    }
+
+    /// Whether the rule matches `rel`, a `/`-separated path relative to the
+    /// ignore file's directory.
+    pub(crate) fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let text = if self.anchored {
+            rel
+        } else {
+            rel.rsplit('/').next().unwrap_or(rel)
+        };
+        let text: Vec<char> = text.chars().collect();
+        wildmatch(&self.glob, &text)
+    }
+}
+
+/// Trailing spaces are dropped unless escaped with a backslash.
+fn trim_unescaped_trailing_spaces(line: &str) -> &str {
+    let mut end = 0;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            end = i + c.len_utf8();
+        } else if c == '\\' {
+            escaped = true;
+            end = i + 1;
+        } else if c != ' ' {
+            end = i + c.len_utf8();
+        }
+    }
+    &line[..end]
+}
+
+// ── Ignore file ───────────────────────────────────────────────────────────────
+
+/// The rules of one ignore file, relative to the directory it applies to.
+#[derive(Debug, Clone)]
+pub(crate) struct Gitignore {
+    /// File name the rules come from (`.gitignore`, `.pt`, ...). Files with
+    /// the same name in nested directories stack on each other.
+    pub(crate) name: String,
+    /// The file the rules were read from.
+    pub(crate) source: PathBuf,
+    /// Directory the patterns are relative to.
+    base: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl Gitignore {
+    /// Read `path` as an ignore file of kind `name`; `None` when it does not
+    /// exist or cannot be read.
+    pub(crate) fn from_file(path: &Path, name: &str, base: &Path) -> Option<Gitignore> {
+        let content = fs::read_to_string(path).ok()?;
+        Some(Gitignore::parse(&content, name, base, path))
+    }
+
+    /// The rules in `content`, read from the file `source`.
+    pub(crate) fn parse(content: &str, name: &str, base: &Path, source: &Path) -> Gitignore {
+        Gitignore::from_lines(content.lines().enumerate(), name, base, source)
+    }
+
+    /// Rules from numbered lines (0-based indices), for dialects that only
+    /// hand part of a file to the gitignore parser.
+    pub(crate) fn from_lines<'a>(
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+        name: &str,
+        base: &Path,
+        source: &Path,
+    ) -> Gitignore {
+        Gitignore {
+            name: name.to_string(),
+            source: source.to_path_buf(),
+            base: base.to_path_buf(),
+            rules: lines
+                .into_iter()
+                .filter_map(|(idx, line)| IgnoreRule::parse(idx + 1, line))
+                .collect(),
+        }
+    }
+
+    /// The last rule matching `path`, if any: the rule that decides whether
+    /// it is ignored (or re-included, for a negated rule). Paths outside
+    /// the base directory never match.
+    pub(crate) fn matched(&self, path: &Path, is_dir: bool) -> Option<&IgnoreRule> {
+        let rel = relative_slash_path(&self.base, path)?;
+        self.rules.iter().rev().find(|r| r.matches(&rel, is_dir))
+    }
+}
+
+/// `path` relative to `base` with `/` separators.
+pub(crate) fn relative_slash_path(base: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(base).ok()?;
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
+}
+
+// ── wildmatch ─────────────────────────────────────────────────────────────────
+
+/// git's wildmatch in pathname mode: `*` and `?` stop at `/`, `**` between
+/// slashes spans directories, `[...]` classes and `\` escapes.
+pub(crate) fn wildmatch(p: &[char], t: &[char]) -> bool {
+    let (mut pi, mut ti) = (0, 0);
+    while pi < p.len() {
+        match p[pi] {
+            '\\' => {
+                pi += 1;
+                if pi == p.len() || ti == t.len() || t[ti] != p[pi] {
+                    return false;
+                }
+            }
+            '?' => {
+                if ti == t.len() || t[ti] == '/' {
+                    return false;
+                }
+            }
+            '*' => {
+                let first = pi;
+                while pi + 1 < p.len() && p[pi + 1] == '*' {
+                    pi += 1;
+                }
+                let rest = &p[pi + 1..];
+                let spans_dirs = pi > first
+                    && (first == 0 || p[first - 1] == '/')
+                    && (rest.is_empty() || rest[0] == '/');
+                if spans_dirs {
+                    if rest.is_empty() {
+                        return true;
+                    }
+                    // `**/` matches zero or more leading directories.
+                    let after = &rest[1..];
+                    return wildmatch(after, &t[ti..])
+                        || (ti..t.len()).any(|i| t[i] == '/' && wildmatch(after, &t[i + 1..]));
+                }
+                for i in ti..=t.len() {
+                    if wildmatch(rest, &t[i..]) {
+                        return true;
+                    }
+                    if i < t.len() && t[i] == '/' {
+                        break;
+                    }
+                }
+                return false;
+            }
+            '[' => {
+                if ti == t.len() || t[ti] == '/' {
+                    return false;
+                }
+                match match_class(p, pi + 1, t[ti]) {
+                    Some((matched, end)) => {
+                        if !matched {
+                            return false;
+                        }
+                        pi = end;
+                    }
+                    // No closing `]`: a literal bracket.
+                    None => {
+                        if t[ti] != '[' {
+                            return false;
+                        }
+                    }
+                }
+            }
+            c => {
+                if ti == t.len() || t[ti] != c {
+                    return false;
+                }
+            }
+        }
+        pi += 1;
+        ti += 1;
+    }
+    ti == t.len()
+}
+
+/// Match `ch` against the class starting at `p[start]` (just after `[`).
+/// Returns whether it matched and the index of the closing `]`.
+fn match_class(p: &[char], start: usize, ch: char) -> Option<(bool, usize)> {
+    let mut i = start;
+    let negated = matches!(p.get(i), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let mut c = *p.get(i)?;
+        if c == ']' && !first {
+            return Some((matched != negated, i));
+        }
+        first = false;
+        if c == '[' && p.get(i + 1) == Some(&':') {
+            if let Some(len) = p[i + 2..].windows(2).position(|w| w == [':', ']']) {
+                let name: String = p[i + 2..i + 2 + len].iter().collect();
+                matched |= posix_class(&name, ch);
+                i += len + 4;
+                continue;
+            }
+        }
+        if c == '\\' {
+            i += 1;
+            c = *p.get(i)?;
+        }
+        if p.get(i + 1) == Some(&'-') && p.get(i + 2).is_some_and(|&e| e != ']') {
+            let mut end = p[i + 2];
+            i += 2;
+            if end == '\\' {
+                i += 1;
+                end = *p.get(i)?;
+            }
+            matched |= c <= ch && ch <= end;
+        } else {
+            matched |= c == ch;
+        }
+        i += 1;
+    }
+}
+
+fn posix_class(name: &str, ch: char) -> bool {
+    match name {
+        "alnum" => ch.is_ascii_alphanumeric(),
+        "alpha" => ch.is_ascii_alphabetic(),
+        "blank" => ch == ' ' || ch == '\t',
+        "cntrl" => ch.is_ascii_control(),
+        "digit" => ch.is_ascii_digit(),
+        "graph" => ch.is_ascii_graphic(),
+        "lower" => ch.is_ascii_lowercase(),
+        "print" => ch.is_ascii_graphic() || ch == ' ',
+        "punct" => ch.is_ascii_punctuation(),
+        "space" => ch.is_ascii_whitespace(),
+        "upper" => ch.is_ascii_uppercase(),
+        "xdigit" => ch.is_ascii_hexdigit(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wm(pattern: &str, text: &str) -> bool {
+        let p: Vec<char> = pattern.chars().collect();
+        let t: Vec<char> = text.chars().collect();
+        wildmatch(&p, &t)
+    }
+
+    fn rule(line: &str) -> IgnoreRule {
+        IgnoreRule::parse(1, line).expect("line should be a rule")
+    }
+
+    /// The verdict of `content` on `path`: `Some(true)` ignored,
+    /// `Some(false)` re-included, `None` no rule.
+    fn verdict(content: &str, path: &str, is_dir: bool) -> Option<bool> {
+        let base = Path::new("/repo");
+        let ignore = Gitignore::parse(content, ".gitignore", base, &base.join(".gitignore"));
+        ignore.matched(&base.join(path), is_dir).map(|r| !r.negated)
+    }
+
+    // ── wildmatch ──
+
+    #[test]
+    fn star_and_question_mark_stop_at_slash() {
+        assert!(wm("*.rs", "main.rs"));
+        assert!(!wm("*.rs", "src/main.rs"));
+        assert!(wm("a?c", "abc"));
+        assert!(!wm("a?c", "a/c"));
+    }
+
+    #[test]
+    fn leading_double_star_matches_any_depth() {
+        assert!(wm("**/foo", "foo"));
+        assert!(wm("**/foo", "a/b/foo"));
+        assert!(!wm("**/foo", "a/foobar"));
+    }
+
+    #[test]
+    fn trailing_double_star_matches_everything_inside() {
+        assert!(wm("abc/**", "abc/x"));
+        assert!(wm("abc/**", "abc/x/y/z"));
+        assert!(!wm("abc/**", "abc"));
+        assert!(!wm("abc/**", "abcd/x"));
+    }
+
+    #[test]
+    fn inner_double_star_matches_zero_or_more_directories() {
+        assert!(wm("a/**/b", "a/b"));
+        assert!(wm("a/**/b", "a/x/b"));
+        assert!(wm("a/**/b", "a/x/y/b"));
+        assert!(!wm("a/**/b", "a/xb"));
+        assert!(!wm("a/**/b", "b"));
+    }
+
+    #[test]
+    fn double_star_inside_a_name_is_a_plain_star() {
+        assert!(wm("a**b", "axyb"));
+        assert!(!wm("a**b", "a/b"));
+    }
+
+    #[test]
+    fn character_classes() {
+        assert!(wm("[[:alpha:]]1", "x1"));
+        assert!(!wm("[[:alpha:]]1", "71"));
+        assert!(wm("[!a-z]", "Q"));
+        assert!(!wm("[!a-z]", "q"));
+        assert!(wm("[^0-9]x", "ax"));
+        assert!(wm("[]]", "]"));
+        assert!(!wm("[a-c]", "/"));
+    }
+
+    #[test]
+    fn unclosed_bracket_is_literal() {
+        assert!(wm("a[b", "a[b"));
+        assert!(!wm("a[b", "ab"));
+    }
+
+    #[test]
+    fn backslash_escapes_the_next_character() {
+        assert!(wm("\\*", "*"));
+        assert!(!wm("\\*", "x"));
+    }
+
+    // ── IgnoreRule ──
+
+    #[test]
+    fn blank_lines_and_comments_are_not_rules() {
+        assert!(IgnoreRule::parse(1, "").is_none());
+        assert!(IgnoreRule::parse(1, "# comment").is_none());
+        assert!(IgnoreRule::parse(1, "\r").is_none());
+    }
+
+    #[test]
+    fn escaped_hash_and_bang_are_literal() {
+        let hash = rule("\\#notes");
+        assert!(!hash.negated);
+        assert!(hash.matches("#notes", false));
+        let bang = rule("\\!important");
+        assert!(!bang.negated);
+        assert!(bang.matches("!important", false));
+        assert!(!bang.matches("important", false));
+    }
+
+    #[test]
+    fn trailing_spaces_are_trimmed_unless_escaped() {
+        let plain = rule("foo   ");
+        assert!(plain.matches("foo", false));
+        assert!(!plain.matches("foo ", false));
+        let escaped = rule("foo\\ ");
+        assert!(escaped.matches("foo ", false));
+        assert!(!escaped.matches("foo", false));
+    }
+
+    #[test]
+    fn unanchored_pattern_matches_the_name_at_any_depth() {
+        let r = rule("*.log");
+        assert!(r.matches("app.log", false));
+        assert!(r.matches("var/log/app.log", false));
+    }
+
+    #[test]
+    fn slash_anchors_the_pattern() {
+        let leading = rule("/build");
+        assert!(leading.matches("build", true));
+        assert!(!leading.matches("src/build", true));
+        let inner = rule("doc/*.txt");
+        assert!(inner.matches("doc/a.txt", false));
+        assert!(!inner.matches("x/doc/a.txt", false));
+        assert!(!inner.matches("doc/sub/a.txt", false));
+    }
+
+    #[test]
+    fn trailing_slash_matches_directories_only() {
+        let r = rule("cache/");
+        assert!(r.matches("cache", true));
+        assert!(r.matches("a/cache", true));
+        assert!(!r.matches("cache", false));
+    }
+
+    #[test]
+    fn negated_rule() {
+        let r = rule("!keep.log");
+        assert!(r.negated);
+        assert!(r.matches("keep.log", false));
+    }
+
+    // ── Gitignore ──
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let content = "*.log\n!keep.log\n";
+        assert_eq!(verdict(content, "app.log", false), Some(true));
+        assert_eq!(verdict(content, "keep.log", false), Some(false));
+        assert_eq!(verdict(content, "main.rs", false), None);
+    }
+
+    #[test]
+    fn negation_cannot_reopen_an_excluded_directory() {
+        // The directory itself is excluded, so the walk never looks inside
+        // it and the `!` line only decides paths it is asked about.
+        let content = "build/\n!build/keep.txt\n";
+        assert_eq!(verdict(content, "build", true), Some(true));
+        assert_eq!(verdict(content, "build/keep.txt", false), Some(false));
+    }
+
+    #[test]
+    fn negation_after_excluding_the_contents_re_includes() {
+        let content = "logs/*\n!logs/keep\n";
+        assert_eq!(verdict(content, "logs", true), None);
+        assert_eq!(verdict(content, "logs/app", false), Some(true));
+        assert_eq!(verdict(content, "logs/keep", false), Some(false));
+    }
+
+    #[test]
+    fn paths_outside_the_base_never_match() {
+        let base = Path::new("/repo/sub");
+        let ignore = Gitignore::parse("*", ".gitignore", base, &base.join(".gitignore"));
+        assert!(ignore.matched(Path::new("/repo/other/x"), false).is_none());
+    }
+}
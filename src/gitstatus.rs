@@ -0,0 +1,363 @@
+// File: src\gitstatus.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: --git-status: reads the index and work tree of the local
+//              repository and marks the nodes of a walked tree.
+// License: MIT
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use git2::{Repository, Status, StatusEntry, StatusOptions};
+
+use crate::config::Config;
+use crate::gitignore::relative_slash_path;
+use crate::ignore::{load_nested_ignore_files, load_root_ignore_files, IgnoreStack};
+use crate::model::{GitStatus, NodeKind, TreeNode};
+use crate::walk::{admit, sort_nodes};
+
+// ── Status map ────────────────────────────────────────────────────────────────
+
+/// `git status` of the work tree around the root of the walk.
+pub(crate) struct StatusMap {
+    /// Top of the work tree, canonicalized like the root.
+    workdir: PathBuf,
+    /// Status by `/`-separated path relative to `workdir`. Untracked and
+    /// ignored directories are reported as a whole, with a trailing `/`.
+    entries: HashMap<String, GitStatus>,
+}
+
+impl StatusMap {
+    /// Read the status of the repository `path` is in. Nothing is fetched;
+    /// only the local `.git` directory and the work tree are looked at.
+    /// Like `git status`, renames are only detected once they are staged;
+    /// before that the old path is deleted and the new one untracked.
+    pub(crate) fn load(path: &Path) -> Result<StatusMap, git2::Error> {
+        let repo = Repository::discover(path)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| git2::Error::from_str("bare repository has no work tree"))?;
+        let workdir = dunce::canonicalize(workdir).unwrap_or_else(|_| workdir.to_path_buf());
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .include_ignored(true)
+            .recurse_ignored_dirs(false)
+            .renames_head_to_index(true);
+        let mut entries = HashMap::new();
+        for entry in repo.statuses(Some(&mut opts))?.iter() {
+            let Some(status) = status_of(entry.status()) else {
+                continue;
+            };
+            if let Some(path) = entry_path(&entry, status) {
+                entries.insert(path, status);
+            }
+        }
+        Ok(StatusMap { workdir, entries })
+    }
+
+    fn get(&self, path: &Path, is_dir: bool) -> Option<GitStatus> {
+        let mut rel = relative_slash_path(&self.workdir, path)?;
+        if is_dir {
+            rel.push('/');
+        }
+        self.entries.get(&rel).copied()
+    }
+}
+
+/// One marker for the index and work tree flags of an entry. A missing
+/// file is deleted whatever else happened to it.
+fn status_of(flags: Status) -> Option<GitStatus> {
+    let status = if flags.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        GitStatus::Deleted
+    } else if flags.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        GitStatus::Renamed
+    } else if flags.contains(Status::INDEX_NEW) {
+        GitStatus::Added
+    } else if flags.contains(Status::WT_NEW) {
+        GitStatus::Untracked
+    } else if flags.contains(Status::IGNORED) {
+        GitStatus::Ignored
+    } else if flags.intersects(
+        Status::INDEX_MODIFIED
+            | Status::INDEX_TYPECHANGE
+            | Status::WT_MODIFIED
+            | Status::WT_TYPECHANGE
+            | Status::CONFLICTED,
+    ) {
+        GitStatus::Modified
+    } else {
+        return None;
+    };
+    Some(status)
+}
+
+/// The path the marker belongs to: the new name of a renamed entry, the
+/// path from the index otherwise.
+fn entry_path(entry: &StatusEntry<'_>, status: GitStatus) -> Option<String> {
+    if status == GitStatus::Renamed {
+        let delta = entry.index_to_workdir().or_else(|| entry.head_to_index());
+        if let Some(path) = delta.and_then(|d| d.new_file().path().map(Path::to_path_buf)) {
+            return Some(path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    entry.path().map(str::to_string)
+}
+
+// ── Annotation ────────────────────────────────────────────────────────────────
+
+/// Mark every node of the tree under `root` with its status, add the
+/// deleted entries back from the index, and mark directories holding
+/// changes as dirty.
+pub(crate) fn annotate(root: &mut TreeNode, status: &StatusMap, config: &Config) {
+    // The entries of --git-tracked and --git-untracked skip the ignore files.
+    let names: &[String] = if config.git_tracked || config.git_untracked {
+        &[]
+    } else {
+        &config.ignore_names
+    };
+    let ignores = IgnoreStack::new(match names {
+        [] => Vec::new(),
+        _ => load_root_ignore_files(&root.path, config),
+    });
+    let mut deleted: Vec<&String> = status
+        .entries
+        .iter()
+        .filter(|(_, &s)| s == GitStatus::Deleted)
+        .map(|(path, _)| path)
+        .collect();
+    deleted.sort();
+    for path in deleted {
+        let Some(rel) = status
+            .workdir
+            .join(path)
+            .strip_prefix(&root.path)
+            .ok()
+            .map(Path::to_path_buf)
+        else {
+            continue;
+        };
+        let parts: Vec<String> = rel
+            .iter()
+            .map(|c| c.to_string_lossy().to_string())
+            .collect();
+        insert_deleted(root, &parts, 1, config, &ignores, names);
+    }
+    mark(root, status, None);
+}
+
+/// Put the deleted entry `parts` (relative to `node`) into the tree,
+/// along with the directories leading to it when they were deleted too.
+/// Entries the walk would not have reached (too deep, excluded, filtered,
+/// inside a directory that was left out) are skipped. `ignores` holds the
+/// ignore files in effect in `node`, and `names` the ones to look for below.
+fn insert_deleted(
+    node: &mut TreeNode,
+    parts: &[String],
+    depth: usize,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    names: &[String],
+) {
+    let Some((name, rest)) = parts.split_first() else {
+        return;
+    };
+    if config.level.is_some_and(|max| depth > max) || node.error.is_some() {
+        return;
+    }
+    let is_dir = !rest.is_empty();
+    if let Some(child) = node.children.iter_mut().find(|c| &c.name == name) {
+        if child.is_dir() && is_dir {
+            let nested = ignores.push(load_nested_ignore_files(&child.path, names));
+            insert_deleted(child, rest, depth + 1, config, &nested, names);
+        }
+        return;
+    }
+    let path = node.path.join(name);
+    // Still on disk but not in the tree: the walk left it out.
+    if fs::symlink_metadata(&path).is_ok() {
+        return;
+    }
+    // The same filters as for the entries on disk; a deleted directory has
+    // no ignore files of its own.
+    let Ok(ignored) = admit(name, &path, is_dir, None, config, ignores, &mut Vec::new()) else {
+        return;
+    };
+    let kind = if is_dir {
+        NodeKind::Directory
+    } else {
+        NodeKind::File
+    };
+    let mut child = TreeNode::new(name.as_str(), path, kind);
+    child.git = Some(GitStatus::Deleted);
+    child.ignored = ignored || node.ignored;
+    insert_deleted(&mut child, rest, depth + 1, config, ignores, names);
+    if is_dir && child.children.is_empty() {
+        return;
+    }
+    node.children.push(child);
+    sort_nodes(&mut node.children, config);
+}
+
+/// Set the status of `node` and everything below it. Directories git
+/// reports as a whole (untracked, ignored, deleted) pass their status on
+/// to contents without one of their own; other directories are dirty
+/// when anything below them changed.
+fn mark(node: &mut TreeNode, status: &StatusMap, inherited: Option<GitStatus>) {
+    let own = node
+        .git
+        .or_else(|| status.get(&node.path, node.is_dir()))
+        .or(inherited);
+    let passed_on = own.filter(|s| {
+        matches!(
+            s,
+            GitStatus::Untracked | GitStatus::Ignored | GitStatus::Deleted
+        )
+    });
+    let mut dirty = false;
+    for child in &mut node.children {
+        mark(child, status, passed_on);
+        dirty |= child.git.is_some_and(|s| s != GitStatus::Ignored);
+    }
+    node.git = own.or((node.is_dir() && dirty).then_some(GitStatus::Dirty));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    /// A repository with `a.txt` and `b.txt` committed.
+    fn committed_repo() -> (TempDir, Repository) {
+        repo_with(&[("a.txt", "a\n"), ("b.txt", "some text to recognize b by\n")])
+    }
+
+    /// A repository with `files` (path and content) committed.
+    fn repo_with(files: &[(&str, &str)]) -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                let file = dir.path().join(path);
+                fs::create_dir_all(file.parent().unwrap()).unwrap();
+                fs::write(file, content).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+        (dir, repo)
+    }
+
+    fn status(dir: &TempDir, path: &str) -> Option<GitStatus> {
+        let map = StatusMap::load(dir.path()).unwrap();
+        map.entries.get(path).copied()
+    }
+
+    #[test]
+    fn unstaged_rename_is_deleted_and_untracked() {
+        let (dir, _repo) = committed_repo();
+        fs::rename(dir.path().join("b.txt"), dir.path().join("moved.txt")).unwrap();
+        assert_eq!(status(&dir, "b.txt"), Some(GitStatus::Deleted));
+        assert_eq!(status(&dir, "moved.txt"), Some(GitStatus::Untracked));
+    }
+
+    #[test]
+    fn staged_rename_is_renamed() {
+        let (dir, repo) = committed_repo();
+        fs::rename(dir.path().join("b.txt"), dir.path().join("moved.txt")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("b.txt")).unwrap();
+        index.add_path(Path::new("moved.txt")).unwrap();
+        index.write().unwrap();
+        assert_eq!(status(&dir, "moved.txt"), Some(GitStatus::Renamed));
+        assert_eq!(status(&dir, "b.txt"), None);
+        assert_eq!(status(&dir, "a.txt"), None);
+    }
+
+    /// A work tree in which everything but `.gitignore` was deleted.
+    fn deleted_repo() -> TempDir {
+        let (dir, _repo) = repo_with(&[
+            (".gitignore", "target/\n*.log\n"),
+            ("a.txt", "a\n"),
+            ("build/x.o", "x\n"),
+            ("target/y.bin", "y\n"),
+            ("logs/z.log", "z\n"),
+        ]);
+        fs::remove_file(dir.path().join("a.txt")).unwrap();
+        for sub in ["build", "target", "logs"] {
+            fs::remove_dir_all(dir.path().join(sub)).unwrap();
+        }
+        dir
+    }
+
+    fn tree(dir: &TempDir, tc: crate::TreeConfig) -> TreeNode {
+        let tc = crate::TreeConfig {
+            path: dir.path().to_path_buf(),
+            git_status: true,
+            excludes: vec!["build".into()],
+            ..tc
+        };
+        let tree = crate::TreeBuilder::from_config(tc).build().unwrap();
+        tree.root_node().clone()
+    }
+
+    fn listing(node: &TreeNode) -> Vec<(String, Option<GitStatus>, bool)> {
+        let mut out = Vec::new();
+        for child in &node.children {
+            out.push((child.name.clone(), child.git, child.ignored));
+            out.extend(
+                listing(child)
+                    .into_iter()
+                    .map(|(name, git, ignored)| (format!("{}/{}", child.name, name), git, ignored)),
+            );
+        }
+        out
+    }
+
+    #[test]
+    fn deleted_entries_go_through_the_exclusions() {
+        let dir = deleted_repo();
+        let root = tree(&dir, crate::TreeConfig::default());
+        assert_eq!(
+            listing(&root),
+            [
+                (".gitignore".to_string(), None, false),
+                ("a.txt".to_string(), Some(GitStatus::Deleted), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn deleted_entries_are_marked_ignored_with_show_ignored() {
+        let dir = deleted_repo();
+        let root = tree(
+            &dir,
+            crate::TreeConfig {
+                show_ignored: true,
+                ..crate::TreeConfig::default()
+            },
+        );
+        let deleted = Some(GitStatus::Deleted);
+        assert_eq!(
+            listing(&root),
+            [
+                (".gitignore".to_string(), None, false),
+                ("a.txt".to_string(), deleted, false),
+                ("build".to_string(), deleted, true),
+                ("build/x.o".to_string(), deleted, true),
+                ("logs".to_string(), deleted, false),
+                ("logs/z.log".to_string(), deleted, true),
+                ("target".to_string(), deleted, true),
+                ("target/y.bin".to_string(), deleted, true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,464 @@
+// File: src\gittree.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: --git-tracked, --git-untracked and --rev: the entries of the
+//              local repository's index, the untracked files of its work
+//              tree or the tree of a revision, as a path listing for
+//              pathtree.
+// License: MIT
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use git2::{ObjectType, Repository, StatusOptions, TreeWalkMode, TreeWalkResult};
+
+use crate::model::{NodeKind, NodeMeta};
+use crate::pathtree::PathEntry;
+
+/// `st_mode` file type bits of a directory, a symlink and a gitlink
+/// (submodule).
+const MODE_DIR: u32 = 0o040000;
+const MODE_SYMLINK: u32 = 0o120000;
+const MODE_GITLINK: u32 = 0o160000;
+
+// ── Index and work tree ───────────────────────────────────────────────────────
+
+/// Every path in the index below `root`, with metadata from the work tree.
+/// Entries deleted from disk are kept, without metadata.
+pub(crate) fn tracked_entries(root: &Path) -> io::Result<Vec<PathEntry>> {
+    let (repo, prefix) = open(root)?;
+    let index = repo.index().map_err(git_error)?;
+    let mut entries = Vec::new();
+    let mut last = Vec::new();
+    for entry in index.iter() {
+        // Unmerged paths appear once per conflict stage, next to each other.
+        if entry.path == last {
+            continue;
+        }
+        last = entry.path.clone();
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let Some(rel) = below(&path, &prefix) else {
+            continue;
+        };
+        let kind = match entry.mode & 0o170000 {
+            MODE_SYMLINK => NodeKind::Symlink,
+            MODE_GITLINK => NodeKind::Directory,
+            _ => NodeKind::File,
+        };
+        entries.push(disk_entry(root, rel, kind));
+    }
+    Ok(entries)
+}
+
+/// The untracked, not ignored files below `root`, one by one (untracked
+/// directories are listed with their contents).
+pub(crate) fn untracked_entries(root: &Path) -> io::Result<Vec<PathEntry>> {
+    let (repo, prefix) = open(root)?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    if !prefix.is_empty() {
+        opts.pathspec(&prefix);
+    }
+    let statuses = repo.statuses(Some(&mut opts)).map_err(git_error)?;
+    let mut entries = Vec::new();
+    for entry in statuses.iter() {
+        if !entry.status().is_wt_new() {
+            continue;
+        }
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        if let Some(rel) = below(path, &prefix) {
+            entries.push(disk_entry(root, rel, NodeKind::File));
+        }
+    }
+    Ok(entries)
+}
+
+/// Metadata for an implied directory of the listing, from the work tree.
+pub(crate) fn disk_dir_meta(path: &Path) -> Option<NodeMeta> {
+    fs::metadata(path).ok().map(|m| NodeMeta::from_metadata(&m))
+}
+
+// ── Revisions ─────────────────────────────────────────────────────────────────
+
+/// The entries of the tree `rev` holds at the place of `root` in the work
+/// tree, read from the object database, and the metadata its directories
+/// get. Sizes are blob sizes, modes the ones recorded in the tree, and the
+/// time is the commit time (when `rev` names a commit or tag).
+pub(crate) fn revision_entries(root: &Path, rev: &str) -> io::Result<(Vec<PathEntry>, NodeMeta)> {
+    let (repo, prefix) = open(root)?;
+    let object = repo.revparse_single(rev).map_err(git_error)?;
+    let modified = object
+        .peel_to_commit()
+        .ok()
+        .and_then(|c| u64::try_from(c.time().seconds()).ok())
+        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+    let mut tree = object.peel_to_tree().map_err(git_error)?;
+    if !prefix.is_empty() {
+        let entry = tree
+            .get_path(Path::new(prefix.trim_end_matches('/')))
+            .map_err(git_error)?;
+        tree = entry
+            .to_object(&repo)
+            .and_then(|o| o.peel_to_tree())
+            .map_err(git_error)?;
+    }
+
+    let odb = repo.odb().map_err(git_error)?;
+    let mut entries = Vec::new();
+    let mut failure = None;
+    let walked = tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        let mode = entry.filemode() as u32;
+        let kind = match (entry.kind(), mode & 0o170000) {
+            (Some(ObjectType::Tree), _) => return TreeWalkResult::Ok,
+            (_, MODE_SYMLINK) => NodeKind::Symlink,
+            (_, MODE_GITLINK) => NodeKind::Directory,
+            _ => NodeKind::File,
+        };
+        let path = PathBuf::from(format!("{}{}", dir, entry.name().unwrap_or_default()));
+        let mut meta = NodeMeta {
+            mode: Some(mode),
+            modified,
+            ..NodeMeta::default()
+        };
+        if kind != NodeKind::Directory {
+            match odb.read_header(entry.id()) {
+                Ok((size, _)) => meta.size = size as u64,
+                Err(e) => {
+                    failure = Some(git_error(e));
+                    return TreeWalkResult::Abort;
+                }
+            }
+        }
+        let mut link_target = None;
+        if kind == NodeKind::Symlink {
+            // Trees record no permissions for links; show them as on disk.
+            meta.mode = Some(MODE_SYMLINK | 0o777);
+            link_target = repo
+                .find_blob(entry.id())
+                .ok()
+                .map(|b| PathBuf::from(String::from_utf8_lossy(b.content()).to_string()));
+        }
+        entries.push(PathEntry {
+            path,
+            kind,
+            metadata: Some(meta),
+            link_target,
+        });
+        TreeWalkResult::Ok
+    });
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    walked.map_err(git_error)?;
+
+    let dir_meta = NodeMeta {
+        mode: Some(MODE_DIR | 0o755),
+        modified,
+        ..NodeMeta::default()
+    };
+    Ok((entries, dir_meta))
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+/// The repository `root` is in and the `/`-terminated path of `root`
+/// inside its work tree (empty at the top).
+fn open(root: &Path) -> io::Result<(Repository, String)> {
+    let repo = Repository::discover(root).map_err(git_error)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| io::Error::other("bare repository has no work tree"))?;
+    let workdir = dunce::canonicalize(workdir)?;
+    let mut prefix = String::new();
+    for part in root.strip_prefix(&workdir).unwrap_or(Path::new("")) {
+        prefix.push_str(&part.to_string_lossy());
+        prefix.push('/');
+    }
+    Ok((repo, prefix))
+}
+
+fn below<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    path.strip_prefix(prefix).filter(|rel| !rel.is_empty())
+}
+
+/// A listed path with its metadata and symlink target from disk, or just
+/// `kind` when it is not there.
+fn disk_entry(root: &Path, rel: &str, kind: NodeKind) -> PathEntry {
+    let full = root.join(rel);
+    let meta = fs::symlink_metadata(&full).ok();
+    let kind = meta
+        .as_ref()
+        .map_or(kind, |m| NodeKind::from_file_type(m.file_type()));
+    PathEntry {
+        path: PathBuf::from(rel),
+        kind,
+        metadata: meta.as_ref().map(NodeMeta::from_metadata),
+        link_target: if kind == NodeKind::Symlink {
+            fs::read_link(&full).ok()
+        } else {
+            None
+        },
+    }
+}
+
+fn git_error(e: git2::Error) -> io::Error {
+    io::Error::other(e.message().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    /// Stage `added` and the changes to tracked files, and commit them on
+    /// top of HEAD.
+    fn commit(repo: &Repository, added: &[&str], message: &str) {
+        let mut index = repo.index().unwrap();
+        for path in added {
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            message,
+            &tree,
+            parent.as_ref().into_iter().collect::<Vec<_>>().as_slice(),
+        )
+        .unwrap();
+    }
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+
+    fn paths(entries: &[PathEntry]) -> Vec<String> {
+        let mut paths: Vec<String> = entries
+            .iter()
+            .map(|e| e.path.to_string_lossy().replace('\\', "/"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// A committed `Cargo.toml`, `src/main.rs` and `.gitignore` (which
+    /// ignores `target/`), next to build output and untracked files.
+    fn work_tree() -> (TempDir, PathBuf, Repository) {
+        let dir = TempDir::new().unwrap();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        let repo = Repository::init(&root).unwrap();
+        write(&root, ".gitignore", "target/\n");
+        write(&root, "Cargo.toml", "[package]\n");
+        write(&root, "src/main.rs", "fn main() {}\n");
+        commit(&repo, &[".gitignore", "Cargo.toml", "src/main.rs"], "init");
+        write(&root, "target/debug/app", "binary");
+        write(&root, "notes.txt", "todo");
+        write(&root, "new/dir/a.rs", "");
+        (dir, root, repo)
+    }
+
+    // ── --git-tracked ──
+
+    #[test]
+    fn tracked_lists_the_index_only() {
+        let (_dir, root, repo) = work_tree();
+        assert_eq!(
+            paths(&tracked_entries(&root).unwrap()),
+            [".gitignore", "Cargo.toml", "src/main.rs"]
+        );
+        // Staged but not committed counts as tracked.
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        assert_eq!(
+            paths(&tracked_entries(&root).unwrap()),
+            [".gitignore", "Cargo.toml", "notes.txt", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn tracked_keeps_deleted_files_without_metadata() {
+        let (_dir, root, _repo) = work_tree();
+        fs::remove_file(root.join("Cargo.toml")).unwrap();
+        let entries = tracked_entries(&root).unwrap();
+        let cargo = entries
+            .iter()
+            .find(|e| e.path == Path::new("Cargo.toml"))
+            .unwrap();
+        assert!(cargo.metadata.is_none());
+        let main = entries
+            .iter()
+            .find(|e| e.path == Path::new("src/main.rs"))
+            .unwrap();
+        assert_eq!(main.metadata.as_ref().unwrap().size, 13);
+    }
+
+    #[test]
+    fn tracked_below_a_subdirectory_is_relative_to_it() {
+        let (_dir, root, _repo) = work_tree();
+        assert_eq!(
+            paths(&tracked_entries(&root.join("src")).unwrap()),
+            ["main.rs"]
+        );
+    }
+
+    #[test]
+    fn tracked_tree_still_applies_the_filters() {
+        let (_dir, root, _repo) = work_tree();
+        let tc = crate::TreeConfig {
+            path: root.clone(),
+            git_tracked: true,
+            pattern: Some("*.rs".into()),
+            prune: true,
+            ..crate::TreeConfig::default()
+        };
+        let tree = crate::TreeBuilder::from_config(tc).build().unwrap();
+        let root = tree.root_node();
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].name, "src");
+        assert_eq!(root.children[0].children[0].name, "main.rs");
+    }
+
+    // ── --git-untracked ──
+
+    #[test]
+    fn untracked_lists_new_files_git_does_not_ignore() {
+        let (_dir, root, _repo) = work_tree();
+        assert_eq!(
+            paths(&untracked_entries(&root).unwrap()),
+            ["new/dir/a.rs", "notes.txt"]
+        );
+        assert_eq!(
+            paths(&untracked_entries(&root.join("new")).unwrap()),
+            ["dir/a.rs"]
+        );
+    }
+
+    #[test]
+    fn listing_outside_a_work_tree_fails() {
+        let dir = TempDir::new().unwrap();
+        assert!(tracked_entries(dir.path()).is_err());
+        assert!(untracked_entries(dir.path()).is_err());
+    }
+
+    // ── --rev ──
+
+    /// `work_tree()` with a tag `v1` on the first commit and a second one
+    /// that grows `Cargo.toml` and adds `src/lib.rs`.
+    fn history() -> (TempDir, PathBuf, Repository) {
+        let (dir, root, repo) = work_tree();
+        {
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.tag_lightweight("v1", head.as_object(), false).unwrap();
+        }
+        write(&root, "Cargo.toml", "[package]\nname = \"x\"\n");
+        write(&root, "src/lib.rs", "");
+        commit(&repo, &["src/lib.rs"], "second");
+        (dir, root, repo)
+    }
+
+    fn size_of(entries: &[PathEntry], path: &str) -> u64 {
+        let entry = entries.iter().find(|e| e.path == Path::new(path)).unwrap();
+        entry.metadata.as_ref().unwrap().size
+    }
+
+    #[test]
+    fn revision_lists_its_own_tree_with_blob_sizes() {
+        let (_dir, root, _repo) = history();
+        let (old, _) = revision_entries(&root, "v1").unwrap();
+        assert_eq!(paths(&old), [".gitignore", "Cargo.toml", "src/main.rs"]);
+        assert_eq!(size_of(&old, "Cargo.toml"), 10);
+
+        // The work tree does not matter, only the object database.
+        fs::remove_file(root.join("Cargo.toml")).unwrap();
+        let (new, _) = revision_entries(&root, "HEAD").unwrap();
+        assert_eq!(
+            paths(&new),
+            [".gitignore", "Cargo.toml", "src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(size_of(&new, "Cargo.toml"), 21);
+    }
+
+    #[test]
+    fn revision_below_a_subdirectory_is_relative_to_it() {
+        let (_dir, root, _repo) = history();
+        let (entries, _) = revision_entries(&root.join("src"), "HEAD~1").unwrap();
+        assert_eq!(paths(&entries), ["main.rs"]);
+    }
+
+    #[test]
+    fn revision_directories_carry_the_commit_time() {
+        let (_dir, root, repo) = history();
+        let (entries, dir_meta) = revision_entries(&root, "HEAD").unwrap();
+        let time = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .time()
+            .seconds();
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(time as u64);
+        assert_eq!(dir_meta.modified, Some(expected));
+        assert_eq!(dir_meta.mode, Some(MODE_DIR | 0o755));
+        assert!(entries
+            .iter()
+            .all(|e| e.metadata.as_ref().unwrap().modified == Some(expected)));
+    }
+
+    #[test]
+    fn unknown_revision_is_an_error() {
+        let (_dir, root, _repo) = history();
+        assert!(revision_entries(&root, "v9").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn revision_modes_map_to_classify_indicators() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let (_dir, root, repo) = work_tree();
+        write(&root, "run.sh", "#!/bin/sh\n");
+        fs::set_permissions(root.join("run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        symlink("Cargo.toml", root.join("link")).unwrap();
+        commit(&repo, &["run.sh", "link"], "modes");
+        fs::remove_file(root.join("run.sh")).unwrap();
+        fs::remove_file(root.join("link")).unwrap();
+
+        let (entries, _) = revision_entries(&root, "HEAD").unwrap();
+        let link = entries
+            .iter()
+            .find(|e| e.path == Path::new("link"))
+            .unwrap();
+        assert_eq!(link.kind, NodeKind::Symlink);
+        assert_eq!(link.link_target.as_deref(), Some(Path::new("Cargo.toml")));
+
+        let tc = crate::TreeConfig {
+            path: root,
+            rev: Some("HEAD".into()),
+            classify: true,
+            noreport: true,
+            ..crate::TreeConfig::default()
+        };
+        let text = crate::TreeBuilder::from_config(tc)
+            .build()
+            .unwrap()
+            .to_string();
+        assert!(text.contains("run.sh*"), "{}", text);
+        assert!(text.contains("link@ (10.00 B) -> Cargo.toml"), "{}", text);
+        assert!(!text.contains("notes.txt"), "{}", text);
+    }
+}
@@ -0,0 +1,350 @@
+// File: src\ignore.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Loading of ignore files (.gitignore, .dockerignore, .pt, ...)
+//              and the exclusion check applied to every entry.
+// License: MIT
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::dialect::{load_matcher, IgnoreMatcher, RuleMatch};
+use crate::git::{expand_home, xdg_config_home, Repo};
+use crate::gitignore::Gitignore;
+use crate::model::HideReason;
+use crate::pattern::wildcard_match;
+
+/// System folders hidden unless `-a/--all` is given.
+pub(crate) const SYSTEM_EXCLUDES: &[&str] = &[
+    ".git",
+    ".svn",
+    ".hg",
+    ".bzr",
+    "_darcs",
+    "CVS",
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+];
+
+/// Ignore files loaded when no `-i/--ignore-file` is given.
+pub(crate) const DEFAULT_IGNORE_FILES: &[&str] = &[
+    ".gitignore",
+    ".dockerignore",
+    ".npmignore",
+    ".eslintignore",
+    ".prettierignore",
+    ".hgignore",
+    ".terraformignore",
+    ".helmignore",
+    ".gcloudignore",
+    ".cfignore",
+    ".slugignore",
+    ".pt",
+];
+
+/// Ignore files their tools only read at the top of the project (build
+/// context, chart, repository). They are not looked for in subdirectories.
+const TOP_LEVEL_IGNORE_FILES: &[&str] = &[
+    ".dockerignore",
+    ".eslintignore",
+    ".prettierignore",
+    ".hgignore",
+    ".terraformignore",
+    ".helmignore",
+    ".gcloudignore",
+    ".cfignore",
+    ".slugignore",
+];
+
+// ── Ignore file loading ───────────────────────────────────────────────────────
+
+/// The ignore files named in `names` that exist in directory `path`, each
+/// parsed in its own dialect.
+pub(crate) fn load_all_ignore_files(path: &Path, names: &[String]) -> Vec<Box<dyn IgnoreMatcher>> {
+    names.iter().filter_map(|f| load_matcher(path, f)).collect()
+}
+
+/// The ignore files of a subdirectory of the walk: like
+/// [`load_all_ignore_files`] without the [`TOP_LEVEL_IGNORE_FILES`].
+pub(crate) fn load_nested_ignore_files(
+    path: &Path,
+    names: &[String],
+) -> Vec<Box<dyn IgnoreMatcher>> {
+    names
+        .iter()
+        .filter(|f| !TOP_LEVEL_IGNORE_FILES.contains(&f.as_str()))
+        .filter_map(|f| load_matcher(path, f))
+        .collect()
+}
+
+/// The file names looked for in every directory: `specific_files` when
+/// given (`-i/--ignore-file`), otherwise [`DEFAULT_IGNORE_FILES`].
+pub(crate) fn ignore_file_names(specific_files: &[String]) -> Vec<String> {
+    if specific_files.is_empty() {
+        DEFAULT_IGNORE_FILES.iter().map(|s| s.to_string()).collect()
+    } else {
+        specific_files.to_vec()
+    }
+}
+
+/// git's exclude sources outside the work tree's `.gitignore` files, lowest
+/// priority first: `core.excludesFile`
+/// (by default `$XDG_CONFIG_HOME/git/ignore`), then `.git/info/exclude`.
+/// Their patterns are relative to the top of the work tree, and they stack
+/// under the `.gitignore` files like in git.
+fn load_git_excludes(repo: &Repo) -> Vec<Box<dyn IgnoreMatcher>> {
+    let excludes_file = match repo.config_value("core", "excludesFile") {
+        Some(file) if !file.is_empty() => Some(expand_home(&file)),
+        Some(_) => None,
+        None => xdg_config_home().map(|dir| dir.join("git").join("ignore")),
+    };
+    let sources = [
+        excludes_file,
+        Some(repo.common_dir.join("info").join("exclude")),
+    ];
+    sources
+        .iter()
+        .flatten()
+        .filter_map(|file| Gitignore::from_file(file, ".gitignore", &repo.root))
+        .map(|gi| Box::new(gi) as Box<dyn IgnoreMatcher>)
+        .collect()
+}
+
+/// The ignore files in effect for the root of the walk, lowest priority
+/// first. Inside a git work tree these are git's exclude sources (when
+/// `.gitignore` files are read) and the ignore files of every directory from
+/// the top of the work tree down to the root's parent; then the root's own.
+/// The directories in between are loaded like subdirectories of the walk,
+/// without the [`TOP_LEVEL_IGNORE_FILES`], so they get the same rules
+/// whether the walk starts above or below them.
+pub(crate) fn load_root_ignore_files(root: &Path, config: &Config) -> Vec<Box<dyn IgnoreMatcher>> {
+    let mut files = Vec::new();
+    if let Some(repo) = Repo::discover(root) {
+        if config.git_excludes && config.ignore_names.iter().any(|n| n == ".gitignore") {
+            files.extend(load_git_excludes(&repo));
+        }
+        let mut ancestors: Vec<&Path> = root
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&repo.root))
+            .collect();
+        ancestors.reverse();
+        for dir in ancestors {
+            if dir == repo.root {
+                files.extend(load_all_ignore_files(dir, &config.ignore_names));
+            } else {
+                files.extend(load_nested_ignore_files(dir, &config.ignore_names));
+            }
+        }
+    }
+    files.extend(load_all_ignore_files(root, &config.ignore_names));
+    files
+}
+
+// ── Ignore stack ──────────────────────────────────────────────────────────────
+
+/// The ignore files in effect while reading one directory: its own, stacked
+/// on those of every directory above it up to the root.
+pub(crate) struct IgnoreStack<'a> {
+    parent: Option<&'a IgnoreStack<'a>>,
+    files: Vec<Box<dyn IgnoreMatcher>>,
+}
+
+impl<'a> IgnoreStack<'a> {
+    pub(crate) fn new(files: Vec<Box<dyn IgnoreMatcher>>) -> IgnoreStack<'static> {
+        IgnoreStack {
+            parent: None,
+            files,
+        }
+    }
+
+    /// A level for a subdirectory with its own `files`, on top of `self`.
+    pub(crate) fn push(&'a self, files: Vec<Box<dyn IgnoreMatcher>>) -> IgnoreStack<'a> {
+        IgnoreStack {
+            parent: Some(self),
+            files,
+        }
+    }
+
+    /// The rule that ignores `path`, if any. As in git, files of the same
+    /// name form one stack and the nearest file with a matching rule decides
+    /// for it, so a nested `!pattern` can re-include what a parent excluded.
+    /// Different kinds of ignore file decide independently, each in its own
+    /// dialect; any of them can exclude.
+    pub(crate) fn ignoring_rule(&self, path: &Path, is_dir: bool) -> Option<RuleMatch<'_>> {
+        let mut decided: Vec<&str> = Vec::new();
+        let levels = std::iter::successors(Some(self), |s| s.parent);
+        for file in levels.flat_map(|s| s.files.iter().rev()) {
+            if decided.contains(&file.name()) {
+                continue;
+            }
+            match file.decide(path, is_dir) {
+                Some(rule) if rule.ignored => return Some(rule),
+                Some(_) => decided.push(file.name()),
+                None => {}
+            }
+        }
+        None
+    }
+}
+
+/// Checked in the priority order of CONFIG.md: exceptions, `--exclude`,
+/// config `default_excludes`, then system excludes and the ignore files in
+/// `ignores`.
+///
+/// `Err` carries what excludes the entry. `Ok(Some(..))` means an
+/// `--exception` pattern kept an entry that would otherwise be excluded.
+pub(crate) fn check_exclusion(
+    name: &str,
+    path: &Path,
+    is_dir: bool,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+) -> Result<Option<(String, HideReason)>, HideReason> {
+    let reason = exclude_reason(name, path, is_dir, config, ignores);
+    match config.exception_patterns.iter().find(|p| p.matches(name)) {
+        Some(exception) => Ok(reason.map(|r| (exception.source(), r))),
+        None => reason.map_or(Ok(None), Err),
+    }
+}
+
+fn exclude_reason(
+    name: &str,
+    path: &Path,
+    is_dir: bool,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+) -> Option<HideReason> {
+    if config.excludes.contains(name) {
+        return Some(HideReason::Exclude(name.to_string()));
+    }
+    if let Some(pattern) = config
+        .default_excludes
+        .iter()
+        .find(|pattern| name_matches(pattern, name))
+    {
+        return Some(HideReason::DefaultExclude(pattern.clone()));
+    }
+    if !config.show_all && SYSTEM_EXCLUDES.contains(&name) {
+        return Some(HideReason::System(name.to_string()));
+    }
+    ignores
+        .ignoring_rule(path, is_dir)
+        .map(|rule| HideReason::IgnoreFile {
+            file: rule.source.to_path_buf(),
+            line: rule.line,
+            rule: rule.text.to_string(),
+        })
+}
+
+/// Wildcard match when `pattern` has `*` or `?`, exact match otherwise.
+fn name_matches(pattern: &str, entry: &str) -> bool {
+    if pattern.contains('*') || pattern.contains('?') {
+        wildcard_match(pattern, entry)
+    } else {
+        entry == pattern
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::TreeConfig;
+
+    /// A work tree with a `.gitignore` and a `.dockerignore` at the top and
+    /// both again in `svc/`, above the directory `svc/app`.
+    fn repo() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let top = dunce::canonicalize(dir.path()).unwrap();
+        git2::Repository::init(&top).unwrap();
+        fs::create_dir_all(top.join("svc").join("app")).unwrap();
+        fs::write(top.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(top.join(".dockerignore"), "**/*.tmp\n").unwrap();
+        fs::write(top.join("svc").join(".gitignore"), "build/\n!keep.log\n").unwrap();
+        fs::write(top.join("svc").join(".dockerignore"), "**/secret.txt\n").unwrap();
+        (dir, top)
+    }
+
+    fn config(root: &Path) -> Config {
+        Config::resolve(&TreeConfig::default(), root)
+    }
+
+    /// The verdict on a few entries of `svc/app`, from `stack`.
+    fn verdicts(stack: &IgnoreStack<'_>, app: &Path) -> Vec<(&'static str, bool)> {
+        [
+            ("x.log", false),
+            ("keep.log", false),
+            ("build", true),
+            ("x.tmp", false),
+            ("secret.txt", false),
+            ("main.rs", false),
+        ]
+        .into_iter()
+        .map(|(name, is_dir)| {
+            let ignored = stack.ignoring_rule(&app.join(name), is_dir).is_some();
+            (name, ignored)
+        })
+        .collect()
+    }
+
+    const EXPECTED: &[(&str, bool)] = &[
+        ("x.log", true),
+        ("keep.log", false),
+        ("build", true),
+        ("x.tmp", true),
+        ("secret.txt", false),
+        ("main.rs", false),
+    ];
+
+    #[test]
+    fn nested_ignore_files_apply_to_their_subtree() {
+        let (_dir, top) = repo();
+        let config = config(&top);
+        let root = IgnoreStack::new(load_root_ignore_files(&top, &config));
+        let svc = root.push(load_nested_ignore_files(
+            &top.join("svc"),
+            &config.ignore_names,
+        ));
+        let app_dir = top.join("svc").join("app");
+        let app = svc.push(load_nested_ignore_files(&app_dir, &config.ignore_names));
+        assert_eq!(verdicts(&app, &app_dir), EXPECTED);
+        // Outside `svc/` its rules do not reach.
+        assert!(root.ignoring_rule(&top.join("build"), true).is_none());
+        assert!(root.ignoring_rule(&top.join("keep.log"), false).is_some());
+    }
+
+    #[test]
+    fn ancestors_up_to_the_work_tree_give_the_same_rules() {
+        let (_dir, top) = repo();
+        let app_dir = top.join("svc").join("app");
+        let config = config(&app_dir);
+        let app = IgnoreStack::new(load_root_ignore_files(&app_dir, &config));
+        assert_eq!(verdicts(&app, &app_dir), EXPECTED);
+    }
+
+    #[test]
+    fn the_root_reads_its_top_level_ignore_files() {
+        let (_dir, top) = repo();
+        let svc_dir = top.join("svc");
+        let config = config(&svc_dir);
+        let svc = IgnoreStack::new(load_root_ignore_files(&svc_dir, &config));
+        assert!(svc
+            .ignoring_rule(&svc_dir.join("secret.txt"), false)
+            .is_some());
+    }
+
+    #[test]
+    fn ancestors_are_not_read_outside_a_work_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let top = dunce::canonicalize(dir.path()).unwrap();
+        fs::create_dir(top.join("sub")).unwrap();
+        fs::write(top.join(".gitignore"), "*.log\n").unwrap();
+        let sub = top.join("sub");
+        let stack = IgnoreStack::new(load_root_ignore_files(&sub, &config(&sub)));
+        assert!(stack.ignoring_rule(&sub.join("x.log"), false).is_none());
+    }
+}
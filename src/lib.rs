@@ -0,0 +1,672 @@
+// File: src\lib.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Library interface of tree2: build a directory tree with the same
+//              filters, metadata and colors as the CLI, then print it or take
+//              it as a string.
+// License: MIT
+
+//! Directory tree rendering with colors, emojis and ignore file support.
+//!
+//! This is the engine behind the `tree2` command line tool. Build a tree with
+//! [`TreeBuilder`] (or from a [`TreeConfig`]), then print it with
+//! [`Tree::print`] or take the plain-text rendering with `to_string()`.
+//!
+//! ```no_run
+//! use tree2::TreeBuilder;
+//!
+//! let tree = TreeBuilder::new()
+//!     .path(".")
+//!     .excludes(vec!["target", ".git"])
+//!     .max_depth(2)
+//!     .build()
+//!     .expect("path should exist");
+//!
+//! tree.print();
+//! let plain = tree.to_string();
+//! # let _ = plain;
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use dunce::canonicalize;
+
+mod color;
+mod config;
+mod dialect;
+mod explain;
+mod git;
+mod gitignore;
+mod gitstatus;
+mod gittree;
+mod ignore;
+mod meta;
+pub mod model;
+mod pathtree;
+mod pattern;
+mod render;
+mod settings;
+mod walk;
+
+use config::Config;
+pub use model::{Explanation, GitStatus, HideReason, NodeError, NodeKind, NodeMeta, TreeNode};
+use render::html::render_html;
+use render::json::render_json;
+use render::markdown::render_markdown;
+use render::text::render_text;
+use render::write_io;
+use render::xml::render_xml;
+pub use settings::Settings;
+
+// ── Public configuration ──────────────────────────────────────────────────────
+
+/// All options understood by the tree builder.
+///
+/// Every field mirrors a `tree2` command line flag. Start from
+/// `TreeConfig::default()` and override what you need:
+///
+/// ```
+/// use tree2::TreeConfig;
+///
+/// let config = TreeConfig {
+///     excludes: vec!["target".into()],
+///     max_depth: Some(3),
+///     dirsfirst: true,
+///     ..Default::default()
+/// };
+/// # let _ = config;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TreeConfig {
+    /// Directory to render. Defaults to `.`.
+    pub path: PathBuf,
+    /// Names excluded by exact match (`--exclude`).
+    pub excludes: Vec<String>,
+    /// Names or wildcards always excluded, usually `default_excludes` from
+    /// the user config file (see [`Settings`]).
+    pub default_excludes: Vec<String>,
+    /// Ignore files to load from the root (`-i`). Empty loads every known
+    /// ignore file (`.gitignore`, `.dockerignore`, `.pt`, ...).
+    pub ignore_files: Vec<String>,
+    /// Skip git's exclude sources (`.git/info/exclude`, `core.excludesFile`)
+    /// that are otherwise read with `.gitignore` inside a work tree
+    /// (`--no-git-excludes`).
+    pub no_git_excludes: bool,
+    /// Patterns that are never excluded (`--exception`). Wildcards and the
+    /// `regex:` prefix are supported.
+    pub exceptions: Vec<String>,
+    /// Show system folders such as `.git` and `.svn` (`-a`).
+    pub show_all: bool,
+    /// List excluded and ignore-file matched entries anyway, marked as
+    /// ignored (`--show-ignored`).
+    pub show_ignored: bool,
+    /// Mark entries with their git status and directories holding changes
+    /// (`--git-status`).
+    pub git_status: bool,
+    /// Build the tree from the git index instead of the directory, listing
+    /// exactly what is tracked (`--git-tracked`).
+    pub git_tracked: bool,
+    /// Only list untracked files that git does not ignore
+    /// (`--git-untracked`).
+    pub git_untracked: bool,
+    /// Render the tree of this git revision (any commit-ish: a commit, tag
+    /// or branch) from the object database instead of the directory
+    /// (`--rev`).
+    pub rev: Option<String>,
+    /// Threads reading and stat'ing directories (`--threads`); 1 walks
+    /// serially, 0 uses one per CPU. The tree is the same either way.
+    pub threads: usize,
+    /// Maximum display depth (`-L`).
+    pub max_depth: Option<usize>,
+    /// List directories only (`-d`).
+    pub dirs_only: bool,
+    /// Print the full path of every entry (`-f`).
+    pub full_path: bool,
+    /// Descend into symbolic links to directories (`-l`). A link back to
+    /// one of its own parents is not followed.
+    pub follow_links: bool,
+    /// Only list files matching this wildcard (`-P`).
+    pub pattern: Option<String>,
+    /// Hide entries matching this wildcard (`-I`).
+    pub ignore_pattern: Option<String>,
+    /// Match `pattern` and `ignore_pattern` case-insensitively.
+    pub ignore_case: bool,
+    /// List directories before files.
+    pub dirsfirst: bool,
+    /// Sort by modification time instead of name (`-t`).
+    pub sort_time: bool,
+    /// Sort by size, largest first (`--sort=size`). Directories sort by
+    /// their accumulated size with `du`.
+    pub sort_size: bool,
+    /// Reverse the sort order (`-r`).
+    pub reverse: bool,
+    /// Print permissions (`-p`).
+    pub protections: bool,
+    /// Print the owner name (`-u`). Unix only.
+    pub owner: bool,
+    /// Print the group name (`-g`). Unix only.
+    pub group: bool,
+    /// Print the owner and group as numeric IDs (`--numeric-uid-gid`).
+    pub numeric_uid_gid: bool,
+    /// Print sizes in bytes (`-s`).
+    pub size_bytes: bool,
+    /// Print human-readable sizes in the metadata prefix (`-h`).
+    pub human_readable: bool,
+    /// Use SI units (powers of 1000) for sizes (`--si`).
+    pub si_units: bool,
+    /// Print the modification date (`-D`).
+    pub date: bool,
+    /// Append type indicators such as `/`, `*` and `@` (`-F`).
+    pub classify: bool,
+    /// Do not descend directories with more entries than this.
+    pub filelimit: Option<usize>,
+    /// Drop empty directories.
+    pub prune: bool,
+    /// Report directory sizes as the sum of their contents: what the
+    /// filters let through, at any depth, and the files `dirs_only` and
+    /// `pattern` leave out.
+    pub du: bool,
+    /// With `du`, count everything below each directory, including what
+    /// the filters hide (`--du-all`).
+    pub du_all: bool,
+    /// Leave out the final `N directories, M files` line.
+    pub noreport: bool,
+    /// Replace non-printable characters with `?` (`-q`).
+    pub quote_chars: bool,
+    /// Wrap names in double quotes (`-Q`).
+    pub quote: bool,
+    /// Stay on the filesystem of the root (`-x`). Unix only.
+    pub xdev: bool,
+    /// Print inode numbers. Unix only.
+    pub inodes: bool,
+    /// Print device numbers. Unix only.
+    pub device: bool,
+    /// Use ANSI colors in [`Tree::print`]. `to_string()` is always plain.
+    pub colors: bool,
+    /// Record why entries were left out, see [`Tree::hidden`] (`--why`).
+    pub why: bool,
+}
+
+impl Default for TreeConfig {
+    fn default() -> Self {
+        TreeConfig {
+            path: PathBuf::from("."),
+            excludes: Vec::new(),
+            default_excludes: Vec::new(),
+            ignore_files: Vec::new(),
+            no_git_excludes: false,
+            exceptions: Vec::new(),
+            show_all: false,
+            show_ignored: false,
+            git_status: false,
+            git_tracked: false,
+            git_untracked: false,
+            rev: None,
+            threads: 1,
+            max_depth: None,
+            dirs_only: false,
+            full_path: false,
+            follow_links: false,
+            pattern: None,
+            ignore_pattern: None,
+            ignore_case: false,
+            dirsfirst: false,
+            sort_time: false,
+            sort_size: false,
+            reverse: false,
+            protections: false,
+            owner: false,
+            group: false,
+            numeric_uid_gid: false,
+            size_bytes: false,
+            human_readable: false,
+            si_units: false,
+            date: false,
+            classify: false,
+            filelimit: None,
+            prune: false,
+            du: false,
+            du_all: false,
+            noreport: false,
+            quote_chars: false,
+            quote: false,
+            xdev: false,
+            inodes: false,
+            device: false,
+            colors: true,
+            why: false,
+        }
+    }
+}
+
+// ── Builder ───────────────────────────────────────────────────────────────────
+
+/// Chainable builder for a [`Tree`].
+#[derive(Debug, Clone, Default)]
+pub struct TreeBuilder {
+    config: TreeConfig,
+}
+
+macro_rules! flag_setters {
+    ($($(#[$doc:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(mut self, on: bool) -> Self {
+                self.config.$name = on;
+                self
+            }
+        )*
+    };
+}
+
+impl TreeBuilder {
+    /// A builder with the default options (current directory, colors on).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from an existing configuration.
+    pub fn from_config(config: TreeConfig) -> Self {
+        TreeBuilder { config }
+    }
+
+    /// Directory to render.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.path = path.into();
+        self
+    }
+
+    /// Names to exclude by exact match.
+    pub fn excludes<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.excludes = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Names or wildcards to exclude in addition to [`TreeBuilder::excludes`].
+    pub fn default_excludes<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.default_excludes = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Ignore files to load instead of the default set.
+    pub fn ignore_files<I, S>(mut self, files: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.ignore_files = files.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Patterns that are never excluded.
+    pub fn exceptions<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.exceptions = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Maximum display depth.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.config.max_depth = Some(depth);
+        self
+    }
+
+    /// Only list files matching this wildcard.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.config.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Hide entries matching this wildcard.
+    pub fn ignore_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.config.ignore_pattern = Some(pattern.into());
+        self
+    }
+
+    /// Do not descend directories with more entries than `limit`.
+    pub fn filelimit(mut self, limit: usize) -> Self {
+        self.config.filelimit = Some(limit);
+        self
+    }
+
+    flag_setters! {
+        /// Skip `.git/info/exclude` and `core.excludesFile`.
+        no_git_excludes,
+        /// Show system folders such as `.git`.
+        show_all,
+        /// List excluded entries anyway, marked as ignored.
+        show_ignored,
+        /// Mark entries with their git status.
+        git_status,
+        /// Build the tree from the git index.
+        git_tracked,
+        /// Only list untracked files.
+        git_untracked,
+        /// List directories only.
+        dirs_only,
+        /// Print the full path of every entry.
+        full_path,
+        /// Follow symbolic links like directories.
+        follow_links,
+        /// Match wildcards case-insensitively.
+        ignore_case,
+        /// List directories before files.
+        dirsfirst,
+        /// Sort by modification time.
+        sort_time,
+        /// Sort by size, largest first.
+        sort_size,
+        /// Reverse the sort order.
+        reverse,
+        /// Print permissions.
+        protections,
+        /// Print the owner name.
+        owner,
+        /// Print the group name.
+        group,
+        /// Print numeric owner and group IDs.
+        numeric_uid_gid,
+        /// Print sizes in bytes.
+        size_bytes,
+        /// Print human-readable sizes in the metadata prefix.
+        human_readable,
+        /// Use SI units for sizes.
+        si_units,
+        /// Print the modification date.
+        date,
+        /// Append type indicators.
+        classify,
+        /// Drop empty directories.
+        prune,
+        /// Report accumulated directory sizes.
+        du,
+        /// Count hidden entries in accumulated sizes.
+        du_all,
+        /// Leave out the summary line.
+        noreport,
+        /// Replace non-printable characters with `?`.
+        quote_chars,
+        /// Wrap names in double quotes.
+        quote,
+        /// Stay on the filesystem of the root.
+        xdev,
+        /// Print inode numbers.
+        inodes,
+        /// Print device numbers.
+        device,
+        /// Use ANSI colors when printing.
+        colors,
+        /// Record why entries were left out.
+        why,
+    }
+
+    /// The configuration collected so far.
+    pub fn config(&self) -> &TreeConfig {
+        &self.config
+    }
+
+    /// Walk the directory into a [`Tree`].
+    ///
+    /// Fails if the path cannot be resolved, or with
+    /// [`TreeConfig::git_tracked`], [`TreeConfig::git_untracked`] or
+    /// [`TreeConfig::rev`] when it is not inside a git work tree or the
+    /// revision does not exist. Unreadable subdirectories do not fail
+    /// the build; they are kept with [`TreeNode::error`] set.
+    pub fn build(self) -> io::Result<Tree> {
+        let abs_path = canonicalize(&self.config.path)?;
+        let config = Config::resolve(&self.config, &abs_path);
+        let mut hidden = Vec::new();
+        let root = walk::build_tree(&abs_path, &config, &mut hidden)?;
+        hidden.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut tree = Tree::new(root, self.config);
+        tree.hidden = hidden;
+        Ok(tree)
+    }
+}
+
+/// Why `path` is or is not listed in the tree `config` describes. Returns
+/// one [`Explanation`] per path component below the root, down to `path`
+/// or to the first component that is hidden (which hides everything
+/// inside it); empty for the root itself.
+///
+/// A relative `path` is taken from the current directory, like on the
+/// command line. Fails when it does not exist or is outside the root.
+///
+/// ```no_run
+/// use tree2::TreeConfig;
+///
+/// for step in tree2::explain(&TreeConfig::default(), "target/debug").unwrap() {
+///     println!("{}", step);
+/// }
+/// ```
+pub fn explain(config: &TreeConfig, path: impl AsRef<Path>) -> io::Result<Vec<Explanation>> {
+    let abs_root = canonicalize(&config.path)?;
+    let path = path.as_ref();
+    // Resolve the parent only, so a symlink is explained as itself.
+    let target = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            canonicalize(parent)?.join(name)
+        }
+        _ => canonicalize(path)?,
+    };
+    fs::symlink_metadata(&target)?;
+    let resolved = Config::resolve(config, &abs_root);
+    explain::explain_path(&resolved, &target)
+}
+
+/// Layout of [`Tree::to_markdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownStyle {
+    /// The text tree without emojis inside a fenced code block.
+    CodeBlock,
+    /// A nested bullet list in which every entry links to its path relative
+    /// to the root.
+    List,
+}
+
+// ── Result ────────────────────────────────────────────────────────────────────
+
+/// Number of directories and files listed in a tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub dirs: u64,
+    pub files: u64,
+    /// Directories listed only because of `--show-ignored`.
+    pub ignored_dirs: u64,
+    /// Files listed only because of `--show-ignored`.
+    pub ignored_files: u64,
+    /// Entries `--git-status` lists as deleted: in the index, but no longer
+    /// on disk. They are not counted as directories or files.
+    pub deleted: u64,
+}
+
+impl Counts {
+    /// The summary line printed at the end, e.g. `\n2 directories, 5 files\n`,
+    /// with `(1 directory, 3 files ignored)` appended when ignored entries
+    /// were listed and `(2 deleted)` when deleted ones were.
+    pub(crate) fn report(&self) -> String {
+        let mut report = format!("\n{}", count_text(self.dirs, self.files));
+        if self.ignored_dirs + self.ignored_files > 0 {
+            report.push_str(&format!(
+                " ({} ignored)",
+                count_text(self.ignored_dirs, self.ignored_files)
+            ));
+        }
+        if self.deleted > 0 {
+            report.push_str(&format!(" ({} deleted)", self.deleted));
+        }
+        report.push('\n');
+        report
+    }
+}
+
+fn count_text(dirs: u64, files: u64) -> String {
+    format!(
+        "{} {}, {} {}",
+        dirs,
+        if dirs == 1 {
+            "directory"
+        } else {
+            "directories"
+        },
+        files,
+        if files == 1 { "file" } else { "files" },
+    )
+}
+
+/// A directory tree, walked and filtered, ready to be rendered.
+///
+/// `to_string()` (via [`fmt::Display`]) gives plain text without ANSI codes,
+/// which is what `tree2 -o` and `tree2 -c` write.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    root: TreeNode,
+    config: TreeConfig,
+    counts: Counts,
+    hidden: Vec<Explanation>,
+}
+
+impl Tree {
+    /// Wrap an existing model, e.g. one built by hand or post-processed.
+    /// `config` supplies the display options used when rendering.
+    pub fn new(root: TreeNode, config: TreeConfig) -> Tree {
+        let counts = root.counts();
+        Tree {
+            root,
+            config,
+            counts,
+            hidden: Vec::new(),
+        }
+    }
+
+    /// Print the tree to stdout, colored unless colors were turned off.
+    /// Lines are written as they are rendered; a closed stdout (`| head`)
+    /// just ends the output.
+    pub fn print(&self) {
+        let _ = self.write_text(&mut io::stdout().lock(), self.config.colors);
+    }
+
+    /// Stream the text rendering into `out` without building the output
+    /// string first, with or without colors. The tree itself is already
+    /// complete, so this saves the copy, not the walk. Wrap files in a
+    /// `BufWriter`; stdout is already line buffered.
+    pub fn write_text(&self, out: &mut dyn io::Write, colors: bool) -> io::Result<()> {
+        write_io(out, |f| render_text(f, &self.root, &self.config, colors))
+    }
+
+    /// Stream the JSON of [`Tree::to_json`] into `out`.
+    pub fn write_json(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        write_io(out, |f| render_json(f, &self.root, &self.config))
+    }
+
+    /// Stream the XML of [`Tree::to_xml`] into `out`.
+    pub fn write_xml(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        write_io(out, |f| render_xml(f, &self.root, &self.config))
+    }
+
+    /// Stream the HTML page of [`Tree::to_html`] into `out`.
+    pub fn write_html(&self, out: &mut dyn io::Write, base_href: &str) -> io::Result<()> {
+        write_io(out, |f| render_html(f, &self.root, &self.config, base_href))
+    }
+
+    /// Stream the Markdown of [`Tree::to_markdown`] into `out`. The code
+    /// block style holds its body back until it knows how long the fence
+    /// must be.
+    pub fn write_markdown(&self, out: &mut dyn io::Write, style: MarkdownStyle) -> io::Result<()> {
+        write_io(out, |f| render_markdown(f, &self.root, &self.config, style))
+    }
+
+    /// The tree as JSON, laid out like GNU `tree -J`: an array holding the
+    /// root directory (with nested `contents`) and a `report` object.
+    ///
+    /// Metadata keys (`mode`, `prot`, `user`, `group`, `size`, `time`,
+    /// `inode`, `dev`) appear when the matching option is enabled; symlinks
+    /// always carry their `target`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        let _ = render_json(&mut out, &self.root, &self.config);
+        out
+    }
+
+    /// The tree as XML, laid out like GNU `tree -X`: `<tree>` with nested
+    /// `<directory>`/`<file>`/`<link>` elements and a `<report>`. Metadata
+    /// is carried in the same attributes as the JSON keys.
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        let _ = render_xml(&mut out, &self.root, &self.config);
+        out
+    }
+
+    /// The tree as a self-contained HTML page (like GNU `tree -H`). Each
+    /// directory is a collapsible `<details>` element, the terminal colors
+    /// become CSS, and every file links to `base_href` followed by its path
+    /// relative to the root.
+    pub fn to_html(&self, base_href: &str) -> String {
+        let mut out = String::new();
+        let _ = render_html(&mut out, &self.root, &self.config, base_href);
+        out
+    }
+
+    /// The tree as Markdown for READMEs and docs. The root is labelled with
+    /// its directory name; metadata requested by the display options (`-p`,
+    /// `-s`, `-h`, `-D`, ...) follows each entry, as inline code in the
+    /// list style.
+    pub fn to_markdown(&self, style: MarkdownStyle) -> String {
+        let mut out = String::new();
+        let _ = render_markdown(&mut out, &self.root, &self.config, style);
+        out
+    }
+
+    /// Canonical path of the root directory.
+    pub fn root(&self) -> &Path {
+        &self.root.path
+    }
+
+    /// The root node of the model.
+    pub fn root_node(&self) -> &TreeNode {
+        &self.root
+    }
+
+    /// The options the tree was built with.
+    pub fn config(&self) -> &TreeConfig {
+        &self.config
+    }
+
+    /// Directories and files listed in the tree.
+    pub fn counts(&self) -> Counts {
+        self.counts
+    }
+
+    /// The entries the walk left out, each with what hid it, plus the
+    /// ones an `--exception` kept. Only recorded when
+    /// [`TreeConfig::why`] is set; entries inside a hidden directory are
+    /// not visited and so not listed.
+    pub fn hidden(&self) -> &[Explanation] {
+        &self.hidden
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        render_text(f, &self.root, &self.config, false)
+    }
+}
@@ -1,50 +1,44 @@
 // File: src\main.rs
 // Author: Hadi Cahyadi <cumulus13@gmail.com>
 // Date: 2025-11-22
-// Description: A high-performance directory tree visualization tool written in Rust with colors,
-//              emojis, and gitignore support. Available as both CLI tool and library crate.
+// Description: Command line front end of tree2. Parses the Linux `tree` compatible
+//              flags and hands them to the library in src/lib.rs.
 // License: MIT
 
-use std::collections::HashSet;
-use std::fs;
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal};
 use std::path::{Path, PathBuf};
 
-use clap::{ArgAction, Parser};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, Command, CommandFactory, FromArgMatches, Parser, ValueEnum};
 use clap_version_flag::colorful_version;
 #[cfg(not(target_os = "android"))]
 use cli_clipboard::{ClipboardContext, ClipboardProvider};
-use dunce::canonicalize;
-use regex::Regex;
-
-// ANSI Color Codes with True Color (24-bit)
-const COLOR_RESET: &str = "\x1b[0m";
-const COLOR_WHITE_ON_RED: &str = "\x1b[1;97;41m";
-const COLOR_ORANGE: &str = "\x1b[38;5;214m";
-
-// True Color (24-bit) ANSI codes - lighter color
-const COLOR_BRIGHT_YELLOW: &str = "\x1b[38;2;255;255;0m"; // #FFFF00
-const COLOR_BRIGHT_CYAN: &str = "\x1b[38;2;0;255;255m"; // #00FFFF
-const COLOR_LIGHT_MAGENTA_TRUE: &str = "\x1b[38;2;255;128;255m"; // Light magenta
-const COLOR_BRIGHT_GREEN: &str = "\x1b[38;2;0;255;128m"; // #00FF80 for symlinks
-const COLOR_GRAY: &str = "\x1b[38;2;160;160;160m"; // #A0A0A0 for meta info
-const COLOR_BRIGHT_WHITE: &str = "\x1b[38;2;230;230;230m"; // #E6E6E6 for summary
+use tree2::{MarkdownStyle, Settings, TreeBuilder, TreeConfig};
 
 #[derive(Parser)]
 #[command(
     name = "tree2",
     about = "Print directory tree with file sizes, exclusions, and .gitignore support\nFully compatible with Linux tree command options.",
-    disable_version_flag = true
+    disable_version_flag = true,
+    disable_help_flag = true,
+    args_override_self = true
 )]
 struct Cli {
     #[arg(short = 'V', long = "version", action = ArgAction::SetTrue)]
     version: bool,
 
+    /// Print help (-h is --human-readable, as in Linux tree)
+    #[arg(long = "help", action = ArgAction::Help)]
+    help: Option<bool>,
+
     #[arg(default_value = ".")]
     path: String,
 
     // ── Original tree2 flags ──────────────────────────────────────────────────
     /// Exclude directories/files (exact match only)
-    #[arg(short, long, num_args = 0..)]
+    #[arg(long, num_args = 0..)]
     exclude: Vec<String>,
 
     /// Copy result to clipboard
@@ -55,6 +49,10 @@ struct Cli {
     #[arg(short = 'i', long, num_args = 0..)]
     ignore_file: Vec<String>,
 
+    /// Do not read git's .git/info/exclude and core.excludesFile (~/.config/git/ignore)
+    #[arg(long = "no-git-excludes")]
+    no_git_excludes: bool,
+
     /// Exception patterns (supports wildcards and regex). Patterns matching these will NOT be excluded
     #[arg(short = 'e', long = "exception", num_args = 0..)]
     exceptions: Vec<String>,
@@ -63,6 +61,54 @@ struct Cli {
     #[arg(short = 'a', long = "all")]
     show_all: bool,
 
+    /// Still list excluded and ignored entries, dimmed and tagged [ignored]
+    #[arg(long = "show-ignored")]
+    show_ignored: bool,
+
+    /// Mark entries with their git status (M, A, D, R, ??, !!) and changed directories with *
+    #[arg(long = "git-status")]
+    git_status: bool,
+
+    /// Build the tree from the git index: exactly the tracked files, whatever is on disk
+    #[arg(long = "git-tracked", conflicts_with = "git_untracked")]
+    git_tracked: bool,
+
+    /// Only show untracked files that git does not ignore
+    #[arg(long = "git-untracked")]
+    git_untracked: bool,
+
+    /// Render the tree of a git revision (commit, tag or branch) without checking it out
+    #[arg(
+        long = "rev",
+        value_name = "COMMIT-ISH",
+        conflicts_with_all = ["git_tracked", "git_untracked", "git_status"]
+    )]
+    rev: Option<String>,
+
+    /// Read directories with N threads (0 = one per CPU); the output is the same as with one
+    #[arg(long = "threads", value_name = "N")]
+    threads: Option<usize>,
+
+    /// Explain why PATH is shown or hidden: the deciding rule and where it comes from
+    #[arg(long = "explain", value_name = "PATH")]
+    explain: Option<PathBuf>,
+
+    /// After the tree, list every hidden entry with the rule that hid it (on stderr)
+    #[arg(long = "why")]
+    why: bool,
+
+    /// Do not read config files (~/.tree2/tree2.toml, .tree2.toml, ...; see CONFIG.md)
+    #[arg(long = "no-config", overrides_with = "profile")]
+    no_config: bool,
+
+    /// Apply the named [profile.NAME] from the config files
+    #[arg(long = "profile", value_name = "NAME", overrides_with = "no_config")]
+    profile: Option<String>,
+
+    /// List the profiles defined in the config files and exit
+    #[arg(long = "list-profiles", conflicts_with = "no_config")]
+    list_profiles: bool,
+
     // ── Linux tree compatibility flags ───────────────────────────────────────
     /// List directories only (no files)
     #[arg(short = 'd', long = "dirs-only")]
@@ -72,7 +118,7 @@ struct Cli {
     #[arg(short = 'f', long = "full-path")]
     full_path: bool,
 
-    /// Follow symbolic links like directories (reserved for future use)
+    /// Follow symbolic links to directories; loops are shown as [recursive, not followed]
     #[arg(short = 'l', long = "follow-links")]
     follow_links: bool,
 
@@ -97,9 +143,18 @@ struct Cli {
     dirsfirst: bool,
 
     /// Sort output by last modification time (oldest first)
-    #[arg(short = 't', long = "sort-time")]
+    #[arg(short = 't', long = "sort-time", overrides_with = "sort")]
     sort_time: bool,
 
+    /// Sort output by name, modification time (same as -t) or size (largest first)
+    #[arg(
+        long = "sort",
+        value_enum,
+        value_name = "KEY",
+        overrides_with = "sort_time"
+    )]
+    sort: Option<SortKey>,
+
     /// Reverse the order of the sort
     #[arg(short = 'r', long = "reverse")]
     reverse: bool,
@@ -116,6 +171,10 @@ struct Cli {
     #[arg(short = 'g', long = "group")]
     group: bool,
 
+    /// Print the owner and group of -u and -g as numeric IDs
+    #[arg(long = "numeric-uid-gid")]
+    numeric_uid_gid: bool,
+
     /// Print size of each file in bytes
     #[arg(short = 's', long = "size")]
     size_bytes: bool,
@@ -144,10 +203,14 @@ struct Cli {
     #[arg(long = "prune")]
     prune: bool,
 
-    /// For each directory report its size as accumulation of all contained file sizes
+    /// For each directory report its size as the sum of the files below it, at any depth, including those -d and -P leave out
     #[arg(long = "du")]
     du: bool,
 
+    /// Like --du, but also count what the excludes, ignore files and -I hide from the tree
+    #[arg(long = "du-all")]
+    du_all: bool,
+
     /// Turn off the file/directory count report at the end
     #[arg(long = "noreport")]
     noreport: bool,
@@ -156,6 +219,22 @@ struct Cli {
     #[arg(short = 'o', long = "output")]
     output_file: Option<String>,
 
+    /// Output format, also used for -o and -c
+    #[arg(long = "format", value_enum, overrides_with_all = ["json", "xml", "html"])]
+    format: Option<Format>,
+
+    /// Print the tree as JSON (compatible with `tree -J`); same as --format json
+    #[arg(short = 'J', long = "json", overrides_with_all = ["format", "xml", "html"])]
+    json: bool,
+
+    /// Print the tree as XML (compatible with `tree -X`); same as --format xml
+    #[arg(short = 'X', long = "xml", overrides_with_all = ["format", "json", "html"])]
+    xml: bool,
+
+    /// Print the tree as a collapsible HTML page; files link to BASE_HREF + their relative path
+    #[arg(short = 'H', long = "html", value_name = "BASE_HREF", overrides_with_all = ["format", "json", "xml"])]
+    html: Option<String>,
+
     /// Turn off ANSI colors
     #[arg(short = 'n', long = "nocolor")]
     nocolor: bool,
@@ -181,905 +260,586 @@ struct Cli {
     device: bool,
 }
 
-// ── Counting state ────────────────────────────────────────────────────────────
-
-struct Counts {
-    dirs: u64,
-    files: u64,
-}
-
-// ── Config (shared traversal settings) ───────────────────────────────────────
-
-// follow_links and ignore_case are stored for completeness / future use.
-// ignore_case is already baked into WildPattern at construction time.
-#[allow(dead_code)]
-struct Config {
-    excludes: HashSet<String>,
-    root_excludes: HashSet<String>,
-    exception_patterns: Vec<Pattern>,
-    dirs_only: bool,
-    full_path: bool,
-    follow_links: bool,
-    level: Option<usize>,
-    pattern: Option<WildPattern>,
-    ignore_pattern: Option<WildPattern>,
-    ignore_case: bool,
-    dirsfirst: bool,
-    sort_time: bool,
-    reverse: bool,
-    protections: bool,
-    owner: bool,
-    group: bool,
-    size_bytes: bool,
-    human_readable: bool,
-    si_units: bool,
-    date: bool,
-    classify: bool,
-    filelimit: Option<usize>,
-    prune: bool,
-    du: bool,
-    quote_chars: bool,
-    quote: bool,
-    xdev: bool,
-    inodes: bool,
-    device: bool,
-    root_dev: Option<u64>,
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The classic tree2 output with emojis and colors
+    Text,
+    /// Same as -J
+    Json,
+    /// Same as -X
+    Xml,
+    /// Same as -H with an empty BASE_HREF (links relative to the page)
+    Html,
+    /// Markdown fenced code block without emojis
+    Markdown,
+    /// Markdown nested list of relative links
+    MarkdownList,
 }
 
-// ── Pattern helpers ───────────────────────────────────────────────────────────
-
-struct WildPattern {
-    raw: String,
-    ignore_case: bool,
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SortKey {
+    Name,
+    Time,
+    Size,
 }
 
-impl WildPattern {
-    fn new(s: &str, ignore_case: bool) -> Self {
-        WildPattern {
-            raw: s.to_string(),
-            ignore_case,
-        }
-    }
+// ── main ──────────────────────────────────────────────────────────────────────
 
-    fn matches(&self, text: &str) -> bool {
-        let (pattern, haystack) = if self.ignore_case {
-            (self.raw.to_lowercase(), text.to_lowercase())
-        } else {
-            (self.raw.clone(), text.to_string())
-        };
-        wildcard_match(&pattern, &haystack)
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 2 && (args[1] == "-V" || args[1] == "--version") {
+        let version = colorful_version!();
+        version.print_and_exit();
     }
-}
 
-enum Pattern {
-    Wildcard(String),
-    Regex(Regex),
-    Exact(String),
-}
+    let matches = Cli::command().get_matches_from(args_with_env_defaults());
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let version_str = colorful_version!();
 
-impl Pattern {
-    fn matches(&self, text: &str) -> bool {
-        match self {
-            Pattern::Wildcard(pattern) => wildcard_match(pattern, text),
-            Pattern::Regex(re) => re.is_match(text),
-            Pattern::Exact(exact) => text == exact,
-        }
+    if cli.version {
+        println!("{}", version_str);
+        std::process::exit(0);
     }
 
-    fn from_string(s: &str) -> Result<Self, String> {
-        if let Some(pattern) = s.strip_prefix("regex:") {
-            match Regex::new(pattern) {
-                Ok(re) => Ok(Pattern::Regex(re)),
-                Err(e) => Err(format!("Invalid regex '{}': {}", pattern, e)),
-            }
-        } else if s.contains('*') || s.contains('?') {
-            Ok(Pattern::Wildcard(s.to_string()))
-        } else {
-            Ok(Pattern::Exact(s.to_string()))
+    let mut default_excludes = Vec::new();
+    if !cli.no_config {
+        let target = dunce::canonicalize(&cli.path).unwrap_or_else(|_| PathBuf::from(&cli.path));
+        let mut settings = Settings::discover(&target);
+        if cli.list_profiles {
+            list_profiles(&settings);
+            std::process::exit(0);
         }
-    }
-}
-
-fn wildcard_match(pattern: &str, text: &str) -> bool {
-    let pc: Vec<char> = pattern.chars().collect();
-    let tc: Vec<char> = text.chars().collect();
-    wm_rec(&pc, &tc, 0, 0)
-}
-
-fn wm_rec(pattern: &[char], text: &[char], pi: usize, ti: usize) -> bool {
-    if pi == pattern.len() {
-        return ti == text.len();
-    }
-    match pattern[pi] {
-        '*' => (ti..=text.len()).any(|i| wm_rec(pattern, text, pi + 1, i)),
-        '?' => ti < text.len() && wm_rec(pattern, text, pi + 1, ti + 1),
-        c => ti < text.len() && text[ti] == c && wm_rec(pattern, text, pi + 1, ti + 1),
-    }
-}
-
-// ── Size formatting ───────────────────────────────────────────────────────────
-
-fn human_size(size: u64) -> String {
-    let units = ["B", "KB", "MB", "GB", "TB"];
-    let mut s = size as f64;
-    for unit in &units {
-        if s < 1024.0 {
-            return format!("{:.2} {}", s, unit);
+        if let Some(ref name) = cli.profile {
+            settings = settings.with_profile(name).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
         }
-        s /= 1024.0;
+        default_excludes = settings.default_excludes.take().unwrap_or_default();
+        apply_settings(&mut cli, settings, &matches);
     }
-    format!("{:.2} PB", s)
-}
 
-fn human_size_si(size: u64) -> String {
-    let units = ["B", "kB", "MB", "GB", "TB"];
-    let mut s = size as f64;
-    for unit in &units {
-        if s < 1000.0 {
-            return format!("{:.2} {}", s, unit);
-        }
-        s /= 1000.0;
-    }
-    format!("{:.2} PB", s)
-}
+    let format = if cli.json {
+        Format::Json
+    } else if cli.xml {
+        Format::Xml
+    } else if cli.html.is_some() {
+        Format::Html
+    } else {
+        cli.format.unwrap_or(Format::Text)
+    };
 
-// ── Ignore file loading ───────────────────────────────────────────────────────
+    // NO_COLOR / CLICOLOR_FORCE follow no-color.org and bixense.com/clicolors;
+    // -n always wins.
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let force_color = std::env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0");
+    // --sort wins over -t from the config files; on the command line the
+    // later of the two does.
+    let sort = cli.sort.unwrap_or(if cli.sort_time {
+        SortKey::Time
+    } else {
+        SortKey::Name
+    });
+    let use_colors = format == Format::Text
+        && !cli.nocolor
+        && (force_color
+            || (!no_color_env
+                && !cli.clipboard
+                && cli.output_file.is_none()
+                && std::io::stdout().is_terminal()));
+    let config = TreeConfig {
+        path: PathBuf::from(&cli.path),
+        excludes: cli.exclude,
+        default_excludes,
+        ignore_files: cli.ignore_file,
+        no_git_excludes: cli.no_git_excludes,
+        exceptions: cli.exceptions,
+        show_all: cli.show_all,
+        show_ignored: cli.show_ignored,
+        git_status: cli.git_status,
+        git_tracked: cli.git_tracked,
+        git_untracked: cli.git_untracked,
+        rev: cli.rev,
+        threads: cli.threads.unwrap_or(1),
+        max_depth: cli.level,
+        dirs_only: cli.dirs_only,
+        full_path: cli.full_path,
+        follow_links: cli.follow_links,
+        pattern: cli.pattern,
+        ignore_pattern: cli.ignore_pattern,
+        ignore_case: cli.ignore_case,
+        dirsfirst: cli.dirsfirst,
+        sort_time: sort == SortKey::Time,
+        sort_size: sort == SortKey::Size,
+        reverse: cli.reverse,
+        protections: cli.protections,
+        owner: cli.owner,
+        group: cli.group,
+        numeric_uid_gid: cli.numeric_uid_gid,
+        size_bytes: cli.size_bytes,
+        human_readable: cli.human_readable,
+        si_units: cli.si_units,
+        date: cli.date,
+        classify: cli.classify,
+        filelimit: cli.filelimit,
+        prune: cli.prune,
+        du: cli.du || cli.du_all,
+        du_all: cli.du_all,
+        noreport: cli.noreport,
+        quote_chars: cli.quote_chars,
+        quote: cli.quote,
+        xdev: cli.xdev,
+        inodes: cli.inodes,
+        device: cli.device,
+        colors: use_colors,
+        why: cli.why,
+    };
 
-fn load_ignore_file(path: &Path, filename: &str) -> HashSet<String> {
-    let ignore_path = path.join(filename);
-    if !ignore_path.exists() {
-        return HashSet::new();
+    if let Some(ref target) = cli.explain {
+        explain(&config, target);
+        return;
     }
-    match fs::read_to_string(ignore_path) {
-        Ok(content) => content
-            .lines()
-            .map(|l| l.trim())
-            .filter(|l| !l.is_empty() && !l.starts_with('#'))
-            .map(|l| l.trim_end_matches('/').to_string())
-            .collect(),
-        Err(_) => HashSet::new(),
-    }
-}
 
-fn load_all_ignore_files(
-    path: &Path,
-    specific_files: Option<&[String]>,
-    show_all: bool,
-) -> HashSet<String> {
-    let mut all = HashSet::new();
-    if !show_all {
-        for s in &[
-            ".git",
-            ".svn",
-            ".hg",
-            ".bzr",
-            "_darcs",
-            "CVS",
-            ".DS_Store",
-            "Thumbs.db",
-            "desktop.ini",
-        ] {
-            all.insert(s.to_string());
-        }
-    }
-    let default_files = [
-        ".gitignore",
-        ".dockerignore",
-        ".npmignore",
-        ".eslintignore",
-        ".prettierignore",
-        ".hgignore",
-        ".terraformignore",
-        ".helmignore",
-        ".gcloudignore",
-        ".cfignore",
-        ".slugignore",
-        ".pt",
-    ];
-    if let Some(files) = specific_files {
-        for f in files {
-            all.extend(load_ignore_file(path, f));
+    let tree = match TreeBuilder::from_config(config).build() {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
+    };
+    // Other formats are printed, saved and copied as-is in one pass; the
+    // default text output is printed in color and rendered a second time as
+    // plain text for the file and the clipboard.
+    let html_base = cli.html.as_deref().unwrap_or("");
+    let render = |out: &mut Sink, colors: bool| match format {
+        Format::Text => tree.write_text(out, colors),
+        Format::Json => tree.write_json(out),
+        Format::Xml => tree.write_xml(out),
+        Format::Html => tree.write_html(out, html_base),
+        Format::Markdown => tree.write_markdown(out, MarkdownStyle::CodeBlock),
+        Format::MarkdownList => tree.write_markdown(out, MarkdownStyle::List),
+    };
+    let (file, clipboard) = (cli.output_file.as_deref(), cli.clipboard);
+    let passes = if format == Format::Text && use_colors {
+        vec![
+            (Sink::new(true, None, false), true),
+            (Sink::new(false, file, clipboard), false),
+        ]
     } else {
-        for f in &default_files {
-            all.extend(load_ignore_file(path, f));
+        vec![(Sink::new(true, file, clipboard), false)]
+    };
+    for (sink, colors) in passes {
+        if let Some(mut sink) = sink {
+            finish_output(render(&mut sink, colors));
+            sink.finish();
         }
     }
-    all
-}
 
-fn should_exclude(
-    entry: &str,
-    excludes: &HashSet<String>,
-    root_excludes: &HashSet<String>,
-    exception_patterns: &[Pattern],
-) -> bool {
-    for p in exception_patterns {
-        if p.matches(entry) {
-            return false;
+    if cli.why {
+        for entry in tree.hidden() {
+            eprintln!("{}", entry);
         }
     }
-    if excludes.contains(entry) {
-        return true;
-    }
-    for pattern in root_excludes {
-        if pattern.contains('*') || pattern.contains('?') {
-            if wildcard_match(pattern, entry) {
-                return true;
-            }
-        } else if entry == pattern {
-            return true;
-        }
-    }
-    false
 }
 
-// ── Unix-only metadata helpers (gated so Windows builds cleanly) ──────────────
-
-#[cfg(unix)]
-fn unix_dev(meta: &fs::Metadata) -> u64 {
-    use std::os::unix::fs::MetadataExt;
-    meta.dev()
-}
+// ── Output ────────────────────────────────────────────────────────────────────
 
-#[cfg(unix)]
-fn unix_ino(meta: &fs::Metadata) -> u64 {
-    use std::os::unix::fs::MetadataExt;
-    meta.ino()
+/// Where the rendered tree goes: stdout, plus the `-o` file and the `-c`
+/// clipboard buffer only when they were asked for. Stdout is line buffered,
+/// so every line shows up as soon as it is complete; the file is written
+/// through a `BufWriter`. A target that fails is dropped with a message and
+/// the others keep going.
+///
+/// The tree is walked in full before the first line is written; the sink
+/// only saves holding a copy of the output as well.
+struct Sink {
+    stdout: Option<Box<dyn io::Write>>,
+    file: Option<(String, BufWriter<File>)>,
+    clipboard: Option<Vec<u8>>,
 }
 
-#[cfg(unix)]
-fn unix_uid(meta: &fs::Metadata) -> u32 {
-    use std::os::unix::fs::MetadataExt;
-    meta.uid()
-}
+impl Sink {
+    /// `None` when there is nothing to write to.
+    fn new(stdout: bool, file: Option<&str>, clipboard: bool) -> Option<Sink> {
+        let file = file.and_then(|path| match File::create(path) {
+            Ok(f) => Some((path.to_string(), BufWriter::new(f))),
+            Err(e) => {
+                eprintln!("❌ Failed to write '{}': {}", path, e);
+                None
+            }
+        });
+        let sink = Sink {
+            stdout: stdout.then(|| Box::new(io::stdout().lock()) as Box<dyn io::Write>),
+            file,
+            clipboard: clipboard.then(Vec::new),
+        };
+        sink.is_open().then_some(sink)
+    }
 
-#[cfg(unix)]
-fn unix_gid(meta: &fs::Metadata) -> u32 {
-    use std::os::unix::fs::MetadataExt;
-    meta.gid()
-}
+    fn is_open(&self) -> bool {
+        self.stdout.is_some() || self.file.is_some() || self.clipboard.is_some()
+    }
 
-#[cfg(unix)]
-fn permission_string(meta: &fs::Metadata) -> String {
-    use std::os::unix::fs::PermissionsExt;
-    let mode = meta.permissions().mode();
-    let file_type = if meta.is_dir() {
-        'd'
-    } else if meta.file_type().is_symlink() {
-        'l'
-    } else {
-        '-'
-    };
-    let bits = [
-        (0o400, 'r'),
-        (0o200, 'w'),
-        (0o100, 'x'),
-        (0o040, 'r'),
-        (0o020, 'w'),
-        (0o010, 'x'),
-        (0o004, 'r'),
-        (0o002, 'w'),
-        (0o001, 'x'),
-    ];
-    let perm: String = bits
-        .iter()
-        .map(|(b, c)| if mode & b != 0 { *c } else { '-' })
-        .collect();
-    format!("[{}{}]", file_type, perm)
+    /// Flush the file and hand the buffer to the clipboard.
+    fn finish(mut self) {
+        let _ = io::Write::flush(&mut self);
+        if let Some((path, _)) = &self.file {
+            eprintln!("✅ Output written to '{}'", path);
+        }
+        if let Some(buffer) = &self.clipboard {
+            copy_to_clipboard(&String::from_utf8_lossy(buffer));
+        }
+    }
 }
 
-#[cfg(not(unix))]
-fn permission_string(_meta: &fs::Metadata) -> String {
-    "[----------]".to_string()
-}
+impl io::Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
 
-#[cfg(unix)]
-fn owner_name(uid: u32) -> String {
-    if let Ok(content) = fs::read_to_string("/etc/passwd") {
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() >= 3 {
-                if let Ok(u) = parts[2].parse::<u32>() {
-                    if u == uid {
-                        return parts[0].to_string();
-                    }
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        if let Some(out) = &mut self.stdout {
+            if let Err(e) = out.write_all(buf) {
+                self.stdout = None;
+                if e.kind() != io::ErrorKind::BrokenPipe {
+                    return Err(e);
                 }
             }
         }
+        if let Some((path, file)) = &mut self.file {
+            if let Err(e) = file.write_all(buf) {
+                eprintln!("❌ Failed to write '{}': {}", path, e);
+                self.file = None;
+            }
+        }
+        if let Some(buffer) = &mut self.clipboard {
+            buffer.extend_from_slice(buf);
+        }
+        if !self.is_open() {
+            // Stops the renderer; finish_output() takes it as a clean end.
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        Ok(())
     }
-    uid.to_string()
-}
 
-#[cfg(unix)]
-fn group_name(gid: u32) -> String {
-    if let Ok(content) = fs::read_to_string("/etc/group") {
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() >= 3 {
-                if let Ok(g) = parts[2].parse::<u32>() {
-                    if g == gid {
-                        return parts[0].to_string();
-                    }
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(out) = &mut self.stdout {
+            if let Err(e) = out.flush() {
+                self.stdout = None;
+                if e.kind() != io::ErrorKind::BrokenPipe {
+                    return Err(e);
                 }
             }
         }
-    }
-    gid.to_string()
-}
-
-// ── Date formatting ───────────────────────────────────────────────────────────
-
-fn format_mtime(meta: &fs::Metadata) -> String {
-    use std::time::UNIX_EPOCH;
-    if let Ok(mtime) = meta.modified() {
-        if let Ok(dur) = mtime.duration_since(UNIX_EPOCH) {
-            let secs = dur.as_secs();
-            let (y, mo, d, h, mi) = secs_to_ymd_hm(secs);
-            return format!("[{:04}-{:02}-{:02} {:02}:{:02}]", y, mo, d, h, mi);
+        if let Some((path, file)) = &mut self.file {
+            if let Err(e) = file.flush() {
+                eprintln!("❌ Failed to write '{}': {}", path, e);
+                self.file = None;
+            }
         }
+        Ok(())
     }
-    "[----/--/-- --:--]".to_string()
 }
 
-fn secs_to_ymd_hm(secs: u64) -> (u64, u64, u64, u64, u64) {
-    let mins = secs / 60;
-    let hours = mins / 60;
-    let days = hours / 24;
-    let mi = mins % 60;
-    let h = hours % 24;
-
-    let mut y: u64 = 1970;
-    let mut remaining = days;
-    loop {
-        let leap =
-            y.is_multiple_of(4) && (!y.is_multiple_of(100) || y.is_multiple_of(400));
-        let days_in_year = if leap { 366 } else { 365 };
-        if remaining < days_in_year {
-            break;
-        }
-        remaining -= days_in_year;
-        y += 1;
-    }
-    let leap = y.is_multiple_of(4) && (!y.is_multiple_of(100) || y.is_multiple_of(400));
-    let month_days = [
-        31u64,
-        if leap { 29 } else { 28 },
-        31,
-        30,
-        31,
-        30,
-        31,
-        31,
-        30,
-        31,
-        30,
-        31,
-    ];
-    let mut mo: u64 = 1;
-    for &md in &month_days {
-        if remaining < md {
-            break;
+/// A reader that went away (`tree2 | head`) ends the output quietly; any
+/// other error writing to stdout is fatal.
+fn finish_output(result: io::Result<()>) {
+    match result {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
-        remaining -= md;
-        mo += 1;
+        _ => {}
     }
-    let d = remaining + 1;
-    (y, mo, d, h, mi)
 }
 
-// ── Classify indicator ────────────────────────────────────────────────────────
-
-fn classify_indicator(meta: &fs::Metadata) -> &'static str {
-    if meta.is_dir() {
-        return "/";
-    }
-    let ft = meta.file_type();
-    if ft.is_symlink() {
-        return "@";
-    }
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
-        if ft.is_fifo() {
-            return "|";
+/// `--explain PATH`: one line per path component below the root.
+fn explain(config: &TreeConfig, target: &Path) {
+    match tree2::explain(config, target) {
+        Ok(steps) if steps.is_empty() => {
+            println!("{}: the root of the tree is always shown", target.display())
         }
-        if ft.is_socket() {
-            return "=";
+        Ok(steps) => {
+            for step in steps {
+                println!("{}", step);
+            }
         }
-        let mode = meta.permissions().mode();
-        if mode & 0o111 != 0 {
-            return "*";
+        Err(e) => {
+            eprintln!("Error: {}: {}", target.display(), e);
+            std::process::exit(1);
         }
     }
-    ""
 }
 
-// ── Sanitize filename ─────────────────────────────────────────────────────────
+// ── Environment defaults ──────────────────────────────────────────────────────
 
-fn sanitize_name(name: &str, quote_chars: bool, quote: bool) -> String {
-    let s = if quote_chars {
-        name.chars()
-            .map(|c| if c.is_control() { '?' } else { c })
-            .collect()
-    } else {
-        name.to_string()
-    };
-    if quote {
-        format!("\"{}\"", s)
-    } else {
-        s
-    }
+/// Command line with the defaults from `TREE2_*` variables and `TREE2_OPTS`
+/// inserted before the real arguments, so that the latter win
+/// (`args_override_self`, and the format flags override each other).
+///
+/// Everything is inserted in `--long=value` form so a multi-value option
+/// such as `--exclude` cannot swallow the arguments that follow.
+fn args_with_env_defaults() -> Vec<OsString> {
+    let mut argv = std::env::args_os();
+    let prog = argv.next().unwrap_or_else(|| "tree2".into());
+    let mut args = vec![prog.clone()];
+    args.extend(env_defaults(std::env::vars(), &prog));
+    args.extend(argv);
+    args
 }
 
-// ── Accumulate dir size (--du) ────────────────────────────────────────────────
-
-fn accumulate_size(path: &Path) -> u64 {
-    let mut total = 0u64;
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.flatten() {
-            if let Ok(meta) = entry.metadata() {
-                if meta.is_dir() {
-                    total += accumulate_size(&entry.path());
-                } else {
-                    total += meta.len();
-                }
+/// The options set by the `TREE2_*` variables among `vars`, in name order,
+/// followed by those in `TREE2_OPTS`. Exits on a malformed `TREE2_OPTS`.
+fn env_defaults(vars: impl IntoIterator<Item = (String, String)>, prog: &OsStr) -> Vec<OsString> {
+    let mut args = Vec::new();
+    let cmd = Cli::command();
+
+    let mut opts = None;
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(k, _)| k.starts_with("TREE2_"))
+        .filter_map(|(k, v)| {
+            if k == "TREE2_OPTS" {
+                opts = Some(v);
+                None
+            } else {
+                Some((k, v))
             }
+        })
+        .collect();
+    vars.sort();
+    for (key, value) in vars {
+        let long = key["TREE2_".len()..].to_lowercase().replace('_', "-");
+        match cmd
+            .get_arguments()
+            .find(|a| a.get_long() == Some(long.as_str()))
+        {
+            Some(arg) => args.extend(env_var_args(arg, &long, &value)),
+            None => eprintln!("Warning: ignoring {}: tree2 has no --{} option", key, long),
         }
     }
-    total
-}
-
-// ── Meta prefix builder ───────────────────────────────────────────────────────
-
-fn build_meta_prefix(meta: &fs::Metadata, config: &Config) -> String {
-    let mut parts = Vec::new();
 
-    if config.inodes {
-        #[cfg(unix)]
-        parts.push(format!("{}", unix_ino(meta)));
-    }
-    if config.device {
-        #[cfg(unix)]
-        parts.push(format!("{}", unix_dev(meta)));
-    }
-    if config.protections {
-        parts.push(permission_string(meta));
-    }
-    if config.owner {
-        #[cfg(unix)]
-        parts.push(owner_name(unix_uid(meta)));
-    }
-    if config.group {
-        #[cfg(unix)]
-        parts.push(group_name(unix_gid(meta)));
-    }
-    if config.size_bytes {
-        parts.push(format!("{}", meta.len()));
-    } else if config.si_units {
-        let sz = human_size_si(meta.len());
-        parts.push(format!("[{}]", sz));
-    } else if config.human_readable {
-        let sz = human_size(meta.len());
-        parts.push(format!("[{}]", sz));
-    }
-    if config.date {
-        parts.push(format_mtime(meta));
+    if let Some(opts) = opts {
+        let words = split_words(&opts);
+        match cmd.clone().try_get_matches_from(
+            std::iter::once(prog.to_owned()).chain(words.into_iter().map(OsString::from)),
+        ) {
+            Ok(matches) => args.extend(explicit_args(&cmd, &matches)),
+            Err(e) => {
+                eprintln!("Error in TREE2_OPTS:");
+                e.exit();
+            }
+        }
     }
+    args
+}
 
-    if parts.is_empty() {
-        String::new()
+/// `TREE2_NAME=value` as `--name` (flags set to a true value), one
+/// `--name=item` per comma separated item (multi-value options) or
+/// `--name=value`.
+fn env_var_args(arg: &clap::Arg, long: &str, value: &str) -> Vec<OsString> {
+    if !arg.get_action().takes_values() {
+        return match value.trim().to_lowercase().as_str() {
+            "" | "0" | "false" | "no" | "off" => Vec::new(),
+            _ => vec![format!("--{}", long).into()],
+        };
+    }
+    let multi = arg.get_num_args().is_some_and(|n| n.max_values() > 1);
+    if multi {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| format!("--{}={}", long, v).into())
+            .collect()
     } else {
-        format!("{} ", parts.join(" "))
+        vec![format!("--{}={}", long, value).into()]
     }
 }
 
-// ── Core tree traversal ───────────────────────────────────────────────────────
-
-struct TreeCtx<'a> {
-    config: &'a Config,
-    output: &'a mut String,
-    use_colors: bool,
-    counts: &'a mut Counts,
-}
-
-fn print_tree(path: &Path, prefix: &str, ctx: &mut TreeCtx<'_>, depth: usize) {
-    if let Some(max) = ctx.config.level {
-        if depth > max {
-            return;
-        }
-    }
-
-    let entries = match fs::read_dir(path) {
-        Ok(e) => {
-            let mut v: Vec<_> = e.collect::<Result<Vec<_>, _>>().unwrap_or_default();
-
-            if ctx.config.sort_time {
-                v.sort_by(|a, b| {
-                    let ta = a.metadata().and_then(|m| m.modified()).ok();
-                    let tb = b.metadata().and_then(|m| m.modified()).ok();
-                    ta.cmp(&tb)
-                });
-            } else {
-                v.sort_by_key(|a| a.file_name());
-            }
-
-            if ctx.config.reverse {
-                v.reverse();
-            }
-
-            if ctx.config.dirsfirst {
-                v.sort_by(|a, b| {
-                    let ad = a.metadata().map(|m| m.is_dir()).unwrap_or(false);
-                    let bd = b.metadata().map(|m| m.is_dir()).unwrap_or(false);
-                    bd.cmp(&ad)
-                });
-            }
-
-            v
+/// The options given in `matches`, rewritten as `--long[=value]`. The path
+/// argument is not carried over.
+fn explicit_args(cmd: &Command, matches: &ArgMatches) -> Vec<OsString> {
+    let mut out = Vec::new();
+    for arg in cmd.get_arguments() {
+        let id = arg.get_id().as_str();
+        if matches.value_source(id) != Some(ValueSource::CommandLine) {
+            continue;
         }
-        Err(_) => {
-            let txt = format!("{}└── 🔒 [Permission Denied]\n", prefix);
-            if ctx.use_colors {
-                print!("{}{}{}", COLOR_WHITE_ON_RED, txt, COLOR_RESET);
-            } else {
-                print!("{}", txt);
-            }
-            ctx.output.push_str(&txt);
-            return;
+        let Some(long) = arg.get_long() else {
+            eprintln!("Warning: TREE2_OPTS: ignoring the path argument");
+            continue;
+        };
+        if !arg.get_action().takes_values() {
+            out.push(format!("--{}", long).into());
+            continue;
         }
-    };
-
-    if let Some(limit) = ctx.config.filelimit {
-        if entries.len() > limit {
-            return;
+        for value in matches.get_raw(id).into_iter().flatten() {
+            let mut token = OsString::from(format!("--{}=", long));
+            token.push(value);
+            out.push(token);
         }
     }
+    out
+}
 
-    let filtered: Vec<_> = entries
-        .iter()
-        .filter(|e| {
-            let name = e.file_name().to_string_lossy().to_string();
-            if should_exclude(
-                &name,
-                &ctx.config.excludes,
-                &ctx.config.root_excludes,
-                &ctx.config.exception_patterns,
-            ) {
-                return false;
+/// Split `TREE2_OPTS` like a POSIX shell would: whitespace separates words,
+/// single quotes are literal, double quotes and backslashes escape.
+fn split_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
             }
-            let meta = match e.metadata() {
-                Ok(m) => m,
-                Err(_) => return true,
-            };
-
-            #[cfg(unix)]
-            if ctx.config.xdev {
-                if let Some(root_dev) = ctx.config.root_dev {
-                    if unix_dev(&meta) != root_dev {
-                        return false;
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
                     }
                 }
             }
-
-            if let Some(ref pat) = ctx.config.ignore_pattern {
-                if pat.matches(&name) {
-                    return false;
-                }
-            }
-
-            if ctx.config.dirs_only && !meta.is_dir() {
-                return false;
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
             }
-
-            if let Some(ref pat) = ctx.config.pattern {
-                if !meta.is_dir() && !pat.matches(&name) {
-                    return false;
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
                 }
             }
-
-            true
-        })
-        .collect();
-
-    for (idx, entry) in filtered.iter().enumerate() {
-        let name = entry.file_name().to_string_lossy().to_string();
-        let display_name = sanitize_name(&name, ctx.config.quote_chars, ctx.config.quote);
-        let connector = if idx == filtered.len() - 1 {
-            "└── "
-        } else {
-            "├── "
-        };
-
-        let meta = match entry.metadata() {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
-
-        let full = if ctx.config.full_path {
-            entry.path().to_string_lossy().to_string()
-        } else {
-            display_name.clone()
-        };
-
-        if meta.is_dir() {
-            if ctx.config.prune {
-                let child_count = fs::read_dir(entry.path()).map(|rd| rd.count()).unwrap_or(0);
-                if child_count == 0 {
-                    continue;
-                }
+            c => {
+                in_word = true;
+                word.push(c);
             }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
 
-            ctx.counts.dirs += 1;
-
-            let meta_str = if ctx.config.du {
-                let sz = accumulate_size(&entry.path());
-                let s = if ctx.config.si_units {
-                    human_size_si(sz)
-                } else {
-                    human_size(sz)
-                };
-                format!("[{}] ", s)
-            } else {
-                build_meta_prefix(&meta, ctx.config)
-            };
-
-            let indicator = if ctx.config.classify { "/" } else { "" };
-            let plain = format!(
-                "{}{}{}📁 {}{}/\n",
-                prefix, connector, meta_str, full, indicator
-            );
-
-            if ctx.use_colors {
-                let colored = format!(
-                    "{}{}{}{}{}{}📁 {}{}/{}{}",
-                    prefix,
-                    connector,
-                    COLOR_GRAY,
-                    meta_str,
-                    COLOR_RESET,
-                    COLOR_BRIGHT_YELLOW,
-                    full,
-                    indicator,
-                    COLOR_RESET,
-                    "\n"
-                );
-                print!("{}", colored);
-                ctx.output.push_str(&plain);
-            } else {
-                print!("{}", plain);
-                ctx.output.push_str(&plain);
+/// Print the profile names, with the profiles each one extends.
+fn list_profiles(settings: &Settings) {
+    if settings.profile.is_empty() {
+        eprintln!(
+            "No profiles defined (add [profile.NAME] tables to a config file, see CONFIG.md)"
+        );
+        return;
+    }
+    for (name, profile) in &settings.profile {
+        match profile.extends {
+            Some(ref parents) if !parents.is_empty() => {
+                println!("{} (extends {})", name, parents.join(", "))
             }
+            _ => println!("{}", name),
+        }
+    }
+}
 
-            let new_prefix = if idx == filtered.len() - 1 {
-                format!("{}    ", prefix)
-            } else {
-                format!("{}│   ", prefix)
-            };
-
-            print_tree(&entry.path(), &new_prefix, ctx, depth + 1);
-        } else {
-            ctx.counts.files += 1;
-
-            let is_symlink = meta.file_type().is_symlink();
-            let meta_str = build_meta_prefix(&meta, ctx.config);
-            let sz = meta.len();
-
-            let indicator = if ctx.config.classify {
-                classify_indicator(&meta)
-            } else {
-                ""
-            };
-
-            let size_display = if ctx.config.size_bytes {
-                format!("{} B", sz)
-            } else if ctx.config.si_units {
-                human_size_si(sz)
-            } else {
-                human_size(sz)
-            };
-
-            let parts: Vec<&str> = size_display.split_whitespace().collect();
-            let (size_val, size_unit) = if parts.len() >= 2 {
-                (parts[0], parts[1])
-            } else {
-                (parts[0], "")
-            };
-
-            let symlink_info = if is_symlink {
-                if let Ok(target) = fs::read_link(entry.path()) {
-                    format!(" -> {}", target.display())
-                } else {
-                    String::new()
-                }
-            } else {
-                String::new()
-            };
-
-            let plain = format!(
-                "{}{}{}📄 {}{} ({} {}){}\n",
-                prefix, connector, meta_str, full, indicator, size_val, size_unit, symlink_info
-            );
-
-            if ctx.use_colors {
-                let name_color = if is_symlink {
-                    COLOR_BRIGHT_GREEN
-                } else {
-                    COLOR_BRIGHT_CYAN
-                };
-                print!("{}{}", prefix, connector);
-                if !meta_str.is_empty() {
-                    print!("{}{}{}", COLOR_GRAY, meta_str, COLOR_RESET);
-                }
-                print!("{}📄 {}{}{}", name_color, full, indicator, COLOR_RESET);
-                if !symlink_info.is_empty() {
-                    print!("{}{}{}", COLOR_BRIGHT_GREEN, symlink_info, COLOR_RESET);
+/// Fill every option that was not given on the command line from the
+/// config files. `--exclude` names are added to the configured ones; the
+/// output format keys are only used when no format flag was given.
+fn apply_settings(cli: &mut Cli, settings: Settings, matches: &ArgMatches) {
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    macro_rules! fill {
+        ($($field:ident <- $key:ident),* $(,)?) => {$(
+            if let Some(v) = settings.$key {
+                if !given(stringify!($field)) {
+                    cli.$field = v;
                 }
-                print!(" (");
-                if sz == 0 {
-                    print!("{}{}", COLOR_WHITE_ON_RED, size_val);
-                } else {
-                    print!("{}{}", COLOR_LIGHT_MAGENTA_TRUE, size_val);
+            }
+        )*};
+    }
+    macro_rules! fill_opt {
+        ($($field:ident <- $key:ident),* $(,)?) => {$(
+            if let Some(v) = settings.$key {
+                if !given(stringify!($field)) {
+                    cli.$field = Some(v);
                 }
-                print!("{} ", COLOR_RESET);
-                print!("{}{}", COLOR_ORANGE, size_unit);
-                println!("{})", COLOR_RESET);
-                ctx.output.push_str(&plain);
-            } else {
-                print!("{}", plain);
-                ctx.output.push_str(&plain);
             }
-        }
+        )*};
     }
-}
-
-// ── main ──────────────────────────────────────────────────────────────────────
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() == 2 && (args[1] == "-V" || args[1] == "--version") {
-        let version = colorful_version!();
-        version.print_and_exit();
+    if let Some(names) = settings.exclude {
+        cli.exclude.extend(names);
     }
-
-    let cli = Cli::parse();
-    let version_str = colorful_version!();
-
-    if cli.version {
-        println!("{}", version_str);
-        std::process::exit(0);
+    fill! {
+        ignore_file <- ignore_files,
+        no_git_excludes <- no_git_excludes,
+        exceptions <- exceptions,
+        clipboard <- clipboard,
+        show_all <- all,
+        show_ignored <- show_ignored,
+        git_status <- git_status,
+        git_tracked <- git_tracked,
+        git_untracked <- git_untracked,
+        dirs_only <- dirs_only,
+        full_path <- full_path,
+        follow_links <- follow_links,
+        ignore_case <- ignore_case,
+        dirsfirst <- dirsfirst,
+        sort_time <- sort_time,
+        reverse <- reverse,
+        protections <- protections,
+        owner <- owner,
+        group <- group,
+        numeric_uid_gid <- numeric_uid_gid,
+        size_bytes <- size,
+        human_readable <- human_readable,
+        si_units <- si,
+        date <- date,
+        classify <- classify,
+        prune <- prune,
+        du <- du,
+        du_all <- du_all,
+        noreport <- noreport,
+        nocolor <- nocolor,
+        quote_chars <- quote_chars,
+        quote <- quote,
+        xdev <- xdev,
+        inodes <- inodes,
+        device <- device,
+        why <- why,
     }
-
-    let path = PathBuf::from(&cli.path);
-    let abs_path = match canonicalize(&path) {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        }
-    };
-
-    let ignore_file_excludes = if cli.ignore_file.is_empty() {
-        load_all_ignore_files(&abs_path, None, cli.show_all)
-    } else {
-        load_all_ignore_files(&abs_path, Some(&cli.ignore_file), cli.show_all)
-    };
-
-    let mut exception_patterns = Vec::new();
-    for exc in &cli.exceptions {
-        match Pattern::from_string(exc) {
-            Ok(p) => exception_patterns.push(p),
-            Err(e) => eprintln!("Warning: {}", e),
-        }
+    fill_opt! {
+        rev <- rev,
+        threads <- threads,
+        level <- level,
+        pattern <- pattern,
+        ignore_pattern <- ignore_pattern,
+        filelimit <- filelimit,
+        output_file <- output,
+    }
+    if settings.sort_size == Some(true) && !given("sort") && !given("sort_time") {
+        cli.sort = Some(SortKey::Size);
     }
 
-    let pattern = cli
-        .pattern
-        .as_deref()
-        .map(|s| WildPattern::new(s, cli.ignore_case));
-    let ignore_pattern = cli
-        .ignore_pattern
-        .as_deref()
-        .map(|s| WildPattern::new(s, cli.ignore_case));
-
-    // root device id for --xdev (Unix only, None on Windows)
-    let root_dev: Option<u64> = {
-        #[cfg(unix)]
-        {
-            if cli.xdev {
-                fs::metadata(&abs_path).ok().map(|m| unix_dev(&m))
-            } else {
-                None
-            }
-        }
-        #[cfg(not(unix))]
-        {
-            None
-        }
-    };
-
-    let config = Config {
-        excludes: cli.exclude.into_iter().collect(),
-        root_excludes: ignore_file_excludes,
-        exception_patterns,
-        dirs_only: cli.dirs_only,
-        full_path: cli.full_path,
-        follow_links: cli.follow_links,
-        level: cli.level,
-        pattern,
-        ignore_pattern,
-        ignore_case: cli.ignore_case,
-        dirsfirst: cli.dirsfirst,
-        sort_time: cli.sort_time,
-        reverse: cli.reverse,
-        protections: cli.protections,
-        owner: cli.owner,
-        group: cli.group,
-        size_bytes: cli.size_bytes,
-        human_readable: cli.human_readable,
-        si_units: cli.si_units,
-        date: cli.date,
-        classify: cli.classify,
-        filelimit: cli.filelimit,
-        prune: cli.prune,
-        du: cli.du,
-        quote_chars: cli.quote_chars,
-        quote: cli.quote,
-        xdev: cli.xdev,
-        inodes: cli.inodes,
-        device: cli.device,
-        root_dev,
-    };
-
-    let mut output = String::new();
-    let use_colors = !cli.clipboard && !cli.nocolor && cli.output_file.is_none();
-
-    let root_text = format!("📂 {}/\n", abs_path.display());
-    if use_colors {
-        print!("{}{}{}", COLOR_BRIGHT_YELLOW, root_text, COLOR_RESET);
-    } else {
-        print!("{}", root_text);
+    if ["format", "json", "xml", "html"].iter().any(|id| given(id)) {
+        return;
     }
-    output.push_str(&root_text);
-
-    let mut counts = Counts { dirs: 0, files: 0 };
-    let mut ctx = TreeCtx {
-        config: &config,
-        output: &mut output,
-        use_colors,
-        counts: &mut counts,
-    };
-    print_tree(&abs_path, "", &mut ctx, 1);
-
-    if !cli.noreport {
-        let report = format!(
-            "\n{} {}, {} {}\n",
-            counts.dirs,
-            if counts.dirs == 1 {
-                "directory"
-            } else {
-                "directories"
-            },
-            counts.files,
-            if counts.files == 1 { "file" } else { "files" },
-        );
-        if use_colors {
-            print!("{}{}{}", COLOR_BRIGHT_WHITE, report, COLOR_RESET);
-        } else {
-            print!("{}", report);
-        }
-        output.push_str(&report);
+    fill! {
+        json <- json,
+        xml <- xml,
     }
-
-    if let Some(ref outfile) = cli.output_file {
-        match fs::write(outfile, &output) {
-            Ok(_) => eprintln!("✅ Output written to '{}'", outfile),
-            Err(e) => eprintln!("❌ Failed to write '{}': {}", outfile, e),
-        }
+    fill_opt! {
+        html <- html,
     }
-
-    if cli.clipboard {
-        copy_to_clipboard(&output);
+    if let Some(name) = settings.format {
+        match Format::from_str(&name, true) {
+            Ok(format) => cli.format = Some(format),
+            Err(e) => eprintln!("Warning: config key 'format': {}", e),
+        }
     }
 }
 
@@ -1132,3 +892,262 @@ fn copy_to_clipboard(text: &str) {
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The command line `args` with `settings` filled in, as `main` does
+    /// after the config files are read.
+    fn cli_with(settings: Settings, args: &[&str]) -> Cli {
+        let matches = Cli::command()
+            .try_get_matches_from(std::iter::once("tree2").chain(args.iter().copied()))
+            .unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        apply_settings(&mut cli, settings, &matches);
+        cli
+    }
+
+    fn settings(toml_text: &str) -> Settings {
+        toml::from_str(toml_text).unwrap()
+    }
+
+    /// The command line `args` with the defaults from `vars` inserted
+    /// before it and `settings` filled in.
+    fn cli_with_env(settings: Settings, vars: &[(&str, &str)], args: &[&str]) -> Cli {
+        let env = env_args(vars);
+        let env: Vec<&str> = env.iter().map(String::as_str).collect();
+        cli_with(settings, &[env.as_slice(), args].concat())
+    }
+
+    fn env_args(vars: &[(&str, &str)]) -> Vec<String> {
+        let vars = vars.iter().map(|(k, v)| (k.to_string(), v.to_string()));
+        env_defaults(vars, OsStr::new("tree2"))
+            .into_iter()
+            .map(|a| a.into_string().unwrap())
+            .collect()
+    }
+
+    // ── Flags ──
+
+    #[test]
+    fn short_e_is_exception_not_exclude() {
+        let cli = cli_with(Settings::default(), &["-e", "*.log", "regex:^test"]);
+        assert_eq!(cli.exceptions, ["*.log", "regex:^test"]);
+        assert!(cli.exclude.is_empty());
+        let cli = cli_with(Settings::default(), &["--exclude", "target"]);
+        assert_eq!(cli.exclude, ["target"]);
+    }
+
+    // ── TREE2_OPTS word splitting ──
+
+    #[test]
+    fn words_are_separated_by_any_whitespace() {
+        assert_eq!(split_words("  -L 2\t\n--prune  "), ["-L", "2", "--prune"]);
+        assert!(split_words("   ").is_empty());
+    }
+
+    #[test]
+    fn single_quotes_are_literal() {
+        assert_eq!(split_words(r#"-e 'a b' 'x\"y'"#), ["-e", "a b", r#"x\"y"#]);
+    }
+
+    #[test]
+    fn double_quotes_keep_spaces_and_honor_backslashes() {
+        assert_eq!(split_words(r#""a b" "say \"hi\"""#), ["a b", r#"say "hi""#]);
+    }
+
+    #[test]
+    fn backslash_escapes_the_next_character() {
+        assert_eq!(split_words(r"a\ b c\\d"), ["a b", r"c\d"]);
+    }
+
+    #[test]
+    fn quotes_join_with_adjacent_text_and_may_be_empty() {
+        assert_eq!(
+            split_words(r#"--exclude='*.log'x "" ''"#),
+            ["--exclude=*.logx", "", ""]
+        );
+    }
+
+    // ── TREE2_* variables ──
+
+    #[test]
+    fn variables_become_long_options_in_name_order() {
+        let vars = [
+            ("TREE2_LEVEL", "3"),
+            ("TREE2_DIRSFIRST", "1"),
+            ("HOME", "/root"),
+        ];
+        assert_eq!(env_args(&vars), ["--dirsfirst", "--level=3"]);
+    }
+
+    #[test]
+    fn false_flag_values_are_left_out() {
+        for value in ["", "0", "false", "No", "OFF"] {
+            assert!(env_args(&[("TREE2_PRUNE", value)]).is_empty(), "{value:?}");
+        }
+        assert_eq!(env_args(&[("TREE2_PRUNE", "yes")]), ["--prune"]);
+    }
+
+    #[test]
+    fn list_variables_are_split_on_commas() {
+        assert_eq!(
+            env_args(&[("TREE2_EXCLUDE", "target, node_modules,,.git ")]),
+            [
+                "--exclude=target",
+                "--exclude=node_modules",
+                "--exclude=.git"
+            ]
+        );
+        let cli = cli_with_env(Settings::default(), &[("TREE2_EXCLUDE", "a,b")], &[]);
+        assert_eq!(cli.exclude, ["a", "b"]);
+    }
+
+    #[test]
+    fn single_value_variables_are_not_split() {
+        assert_eq!(env_args(&[("TREE2_PATTERN", "a,b")]), ["--pattern=a,b"]);
+    }
+
+    #[test]
+    fn opts_come_after_the_variables() {
+        let vars = [("TREE2_OPTS", "-L 4 --exclude 'a b'"), ("TREE2_LEVEL", "2")];
+        assert_eq!(env_args(&vars), ["--level=2", "--exclude=a b", "--level=4"]);
+        assert_eq!(cli_with_env(Settings::default(), &vars, &[]).level, Some(4));
+    }
+
+    // ── Precedence ──
+
+    #[test]
+    fn config_then_environment_then_command_line() {
+        let config = || settings("level = 1\nprune = true");
+        let env = [("TREE2_LEVEL", "2")];
+        assert_eq!(cli_with_env(config(), &[], &[]).level, Some(1));
+        assert_eq!(cli_with_env(config(), &env, &[]).level, Some(2));
+        let cli = cli_with_env(config(), &env, &["-L", "3"]);
+        assert_eq!(cli.level, Some(3));
+        assert!(cli.prune);
+    }
+
+    #[test]
+    fn last_format_flag_wins() {
+        let cli = cli_with(Settings::default(), &["-J", "-X"]);
+        assert!(cli.xml && !cli.json);
+        let cli = cli_with(Settings::default(), &["-X", "-H", "base/"]);
+        assert!(cli.html.as_deref() == Some("base/") && !cli.xml);
+        let cli = cli_with(Settings::default(), &["-H", "", "--format", "markdown"]);
+        assert!(cli.format == Some(Format::Markdown) && cli.html.is_none());
+        let cli = cli_with(Settings::default(), &["--format", "xml", "-J"]);
+        assert!(cli.json && cli.format.is_none());
+    }
+
+    #[test]
+    fn command_line_format_replaces_one_from_the_environment() {
+        let env = [("TREE2_JSON", "1")];
+        let cli = cli_with_env(Settings::default(), &env, &["-X"]);
+        assert!(cli.xml && !cli.json);
+        let cli = cli_with_env(Settings::default(), &[("TREE2_FORMAT", "xml")], &["-J"]);
+        assert!(cli.json && cli.format.is_none());
+    }
+
+    #[test]
+    fn environment_format_replaces_the_config_one() {
+        let cli = cli_with_env(settings("json = true"), &[("TREE2_XML", "1")], &[]);
+        assert!(cli.xml && !cli.json);
+    }
+
+    // ── Profiles ──
+
+    #[test]
+    fn profile_fills_in_what_the_command_line_leaves_out() {
+        let profile = settings("[profile.p]\nlevel = 2\nprune = true\njson = true")
+            .with_profile("p")
+            .unwrap();
+        let cli = cli_with(profile, &[]);
+        assert_eq!(cli.level, Some(2));
+        assert!(cli.prune);
+        assert!(cli.json);
+    }
+
+    #[test]
+    fn command_line_flags_override_the_profile() {
+        let profile =
+            settings("[profile.p]\nlevel = 2\nprune = true\njson = true\ndirsfirst = true")
+                .with_profile("p")
+                .unwrap();
+        let cli = cli_with(profile, &["-L", "5", "--format", "xml"]);
+        assert_eq!(cli.level, Some(5));
+        assert!(cli.prune);
+        assert!(cli.dirsfirst);
+        assert!(!cli.json);
+        assert!(cli.format == Some(Format::Xml));
+    }
+
+    // ── Output ──
+
+    /// Stdout of a process whose reader went away, or one that fails with
+    /// `kind`.
+    struct Failing(io::ErrorKind);
+
+    impl io::Write for Failing {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(self.0.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn sink(stdout: io::ErrorKind, clipboard: bool) -> Sink {
+        Sink {
+            stdout: Some(Box::new(Failing(stdout))),
+            file: None,
+            clipboard: clipboard.then(Vec::new),
+        }
+    }
+
+    fn sample_tree() -> (tempfile::TempDir, tree2::Tree) {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            std::fs::write(dir.path().join(name), name).unwrap();
+        }
+        let config = TreeConfig {
+            path: dir.path().to_path_buf(),
+            ..TreeConfig::default()
+        };
+        let tree = TreeBuilder::from_config(config).build().unwrap();
+        (dir, tree)
+    }
+
+    #[test]
+    fn closed_stdout_stops_the_renderer_quietly() {
+        let (_dir, tree) = sample_tree();
+        let mut out = sink(io::ErrorKind::BrokenPipe, false);
+        let result = tree.write_text(&mut out, false);
+        assert_eq!(
+            result.as_ref().unwrap_err().kind(),
+            io::ErrorKind::BrokenPipe
+        );
+        assert!(out.stdout.is_none());
+        // Returns instead of exiting with an error.
+        finish_output(result);
+        finish_output(Ok(()));
+    }
+
+    #[test]
+    fn closed_stdout_leaves_the_clipboard_buffer_complete() {
+        let (_dir, tree) = sample_tree();
+        let mut out = sink(io::ErrorKind::BrokenPipe, true);
+        tree.write_text(&mut out, false).unwrap();
+        let copied = String::from_utf8(out.clipboard.unwrap()).unwrap();
+        assert_eq!(copied, tree.to_string());
+    }
+
+    #[test]
+    fn other_stdout_errors_are_passed_on() {
+        let mut out = sink(io::ErrorKind::PermissionDenied, true);
+        let err = io::Write::write_all(&mut out, b"line\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,393 @@
+// File: src\meta.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: File metadata helpers: sizes, permissions, owners, dates and
+//              type indicators.
+// License: MIT
+
+#[cfg(unix)]
+use std::collections::BTreeMap;
+#[cfg(unix)]
+use std::ffi::CStr;
+use std::fs;
+#[cfg(unix)]
+use std::mem::MaybeUninit;
+use std::path::Path;
+#[cfg(unix)]
+use std::ptr;
+#[cfg(unix)]
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::model::{NodeKind, NodeMeta};
+
+// ── Size formatting ───────────────────────────────────────────────────────────
+
+pub(crate) fn human_size(size: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut s = size as f64;
+    for unit in &units {
+        if s < 1024.0 {
+            return format!("{:.2} {}", s, unit);
+        }
+        s /= 1024.0;
+    }
+    format!("{:.2} PB", s)
+}
+
+pub(crate) fn human_size_si(size: u64) -> String {
+    let units = ["B", "kB", "MB", "GB", "TB"];
+    let mut s = size as f64;
+    for unit in &units {
+        if s < 1000.0 {
+            return format!("{:.2} {}", s, unit);
+        }
+        s /= 1000.0;
+    }
+    format!("{:.2} PB", s)
+}
+
+// ── Unix-only metadata helpers (gated so Windows builds cleanly) ──────────────
+
+#[cfg(unix)]
+pub(crate) fn unix_dev(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.dev()
+}
+
+/// Device id for `--xdev`; `None` where there is none.
+pub(crate) fn device_id(meta: &fs::Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        Some(unix_dev(meta))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = meta;
+        None
+    }
+}
+
+/// `(device, inode)` of a directory, which `-l` uses to notice loops;
+/// `None` where there are no inode numbers.
+pub(crate) fn file_id(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((meta.dev(), meta.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = meta;
+        None
+    }
+}
+
+// ── Permissions ───────────────────────────────────────────────────────────────
+
+/// `[drwxr-xr-x]` style string; `mode` is `None` where there is no Unix mode.
+pub(crate) fn permission_string(kind: NodeKind, mode: Option<u32>) -> String {
+    let mode = match mode {
+        Some(m) => m,
+        None => return "[----------]".to_string(),
+    };
+    let file_type = match kind {
+        NodeKind::Directory => 'd',
+        NodeKind::Symlink => 'l',
+        _ => '-',
+    };
+    let bits = [
+        (0o400, 'r'),
+        (0o200, 'w'),
+        (0o100, 'x'),
+        (0o040, 'r'),
+        (0o020, 'w'),
+        (0o010, 'x'),
+        (0o004, 'r'),
+        (0o002, 'w'),
+        (0o001, 'x'),
+    ];
+    let perm: String = bits
+        .iter()
+        .map(|(b, c)| if mode & b != 0 { *c } else { '-' })
+        .collect();
+    format!("[{}{}]", file_type, perm)
+}
+
+// ── Owner / group names (Unix only) ───────────────────────────────────────────
+
+/// Name of the user `uid`, or the number when the user database has no
+/// entry. Names come from the system (`getpwuid_r`), so NSS sources such as
+/// LDAP and SSSD are honoured; each uid is looked up once per run.
+#[cfg(unix)]
+pub(crate) fn owner_name(uid: u32) -> String {
+    static NAMES: Mutex<BTreeMap<u32, String>> = Mutex::new(BTreeMap::new());
+    cached_name(&NAMES, uid, |buf| {
+        let mut entry = MaybeUninit::<libc::passwd>::uninit();
+        let mut found = ptr::null_mut();
+        // SAFETY: every pointer is valid for the call and `buf.len()` is the
+        // size of `buf`.
+        let rc = unsafe {
+            libc::getpwuid_r(
+                uid,
+                entry.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut found,
+            )
+        };
+        // SAFETY: a non-null `found` points at the filled-in `entry`.
+        let name = if found.is_null() {
+            ptr::null()
+        } else {
+            unsafe { (*found).pw_name }
+        };
+        (rc, name)
+    })
+}
+
+/// Name of the group `gid` (`getgrgid_r`), cached like [`owner_name`].
+#[cfg(unix)]
+pub(crate) fn group_name(gid: u32) -> String {
+    static NAMES: Mutex<BTreeMap<u32, String>> = Mutex::new(BTreeMap::new());
+    cached_name(&NAMES, gid, |buf| {
+        let mut entry = MaybeUninit::<libc::group>::uninit();
+        let mut found = ptr::null_mut();
+        // SAFETY: as in owner_name.
+        let rc = unsafe {
+            libc::getgrgid_r(
+                gid,
+                entry.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut found,
+            )
+        };
+        // SAFETY: a non-null `found` points at the filled-in `entry`.
+        let name = if found.is_null() {
+            ptr::null()
+        } else {
+            unsafe { (*found).gr_name }
+        };
+        (rc, name)
+    })
+}
+
+/// The cached name of `id`, looked up with `lookup` the first time. The
+/// lookup gets a string buffer, grown while the call reports `ERANGE`,
+/// and returns the error code and the name it found in the buffer.
+#[cfg(unix)]
+fn cached_name(
+    cache: &Mutex<BTreeMap<u32, String>>,
+    id: u32,
+    lookup: impl Fn(&mut [libc::c_char]) -> (libc::c_int, *const libc::c_char),
+) -> String {
+    let mut names = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    names
+        .entry(id)
+        .or_insert_with(|| {
+            let mut buf: Vec<libc::c_char> = vec![0; 1024];
+            loop {
+                let (rc, name) = lookup(&mut buf);
+                if rc == libc::ERANGE && buf.len() < 1 << 20 {
+                    buf.resize(buf.len() * 2, 0);
+                } else if rc != 0 || name.is_null() {
+                    return id.to_string();
+                } else {
+                    // SAFETY: the name is a NUL-terminated string inside
+                    // `buf`, which is still alive.
+                    return unsafe { CStr::from_ptr(name) }
+                        .to_string_lossy()
+                        .into_owned();
+                }
+            }
+        })
+        .clone()
+}
+
+// ── Date formatting ───────────────────────────────────────────────────────────
+
+pub(crate) fn format_mtime(modified: Option<SystemTime>) -> String {
+    if let Some(mtime) = modified {
+        if let Ok(dur) = mtime.duration_since(UNIX_EPOCH) {
+            let secs = dur.as_secs();
+            let (y, mo, d, h, mi) = secs_to_ymd_hm(secs);
+            return format!("[{:04}-{:02}-{:02} {:02}:{:02}]", y, mo, d, h, mi);
+        }
+    }
+    "[----/--/-- --:--]".to_string()
+}
+
+fn secs_to_ymd_hm(secs: u64) -> (u64, u64, u64, u64, u64) {
+    let mins = secs / 60;
+    let hours = mins / 60;
+    let days = hours / 24;
+    let mi = mins % 60;
+    let h = hours % 24;
+
+    let mut y: u64 = 1970;
+    let mut remaining = days;
+    loop {
+        let leap = y.is_multiple_of(4) && (!y.is_multiple_of(100) || y.is_multiple_of(400));
+        let days_in_year = if leap { 366 } else { 365 };
+        if remaining < days_in_year {
+            break;
+        }
+        remaining -= days_in_year;
+        y += 1;
+    }
+    let leap = y.is_multiple_of(4) && (!y.is_multiple_of(100) || y.is_multiple_of(400));
+    let month_days = [
+        31u64,
+        if leap { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+    let mut mo: u64 = 1;
+    for &md in &month_days {
+        if remaining < md {
+            break;
+        }
+        remaining -= md;
+        mo += 1;
+    }
+    let d = remaining + 1;
+    (y, mo, d, h, mi)
+}
+
+// ── Classify indicator ────────────────────────────────────────────────────────
+
+pub(crate) fn classify_indicator(kind: NodeKind, meta: Option<&NodeMeta>) -> &'static str {
+    match kind {
+        NodeKind::Directory => "/",
+        NodeKind::Symlink => "@",
+        NodeKind::Fifo => "|",
+        NodeKind::Socket => "=",
+        NodeKind::File if meta.is_some_and(|m| m.is_executable()) => "*",
+        NodeKind::File => "",
+    }
+}
+
+// ── Sanitize filename ─────────────────────────────────────────────────────────
+
+pub(crate) fn sanitize_name(name: &str, quote_chars: bool, quote: bool) -> String {
+    let s = if quote_chars {
+        name.chars()
+            .map(|c| if c.is_control() { '?' } else { c })
+            .collect()
+    } else {
+        name.to_string()
+    };
+    if quote {
+        format!("\"{}\"", s)
+    } else {
+        s
+    }
+}
+
+// ── Accumulate dir size (--du) ────────────────────────────────────────────────
+
+/// Size of everything below `path`, for the parts of the tree `--du-all`
+/// counts without listing. Symlinks count as links and are not followed;
+/// with `device`, other filesystems are left out.
+pub(crate) fn accumulate_size(path: &Path, device: Option<u64>) -> u64 {
+    let mut total = 0u64;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            if let Ok(meta) = entry.metadata() {
+                if device.is_some_and(|dev| device_id(&meta) != Some(dev)) {
+                    continue;
+                }
+                if meta.is_dir() {
+                    total += accumulate_size(&entry.path(), device);
+                } else {
+                    total += meta.len();
+                }
+            }
+        }
+    }
+    total
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    /// A lookup for `cached_name` that writes `name` into the buffer once
+    /// it holds at least `needs` characters, and counts its calls.
+    fn lookup<'a>(
+        name: &'a str,
+        needs: usize,
+        calls: &'a Cell<usize>,
+    ) -> impl Fn(&mut [libc::c_char]) -> (libc::c_int, *const libc::c_char) + 'a {
+        move |buf| {
+            calls.set(calls.get() + 1);
+            if buf.len() < needs {
+                return (libc::ERANGE, ptr::null());
+            }
+            for (slot, byte) in buf.iter_mut().zip(name.bytes().chain([0])) {
+                *slot = byte as libc::c_char;
+            }
+            (0, buf.as_ptr())
+        }
+    }
+
+    #[test]
+    fn names_come_from_the_user_and_group_databases() {
+        assert_eq!(owner_name(0), "root");
+        // Group 0 is `root` on Linux and `wheel` on the BSDs.
+        assert!(!group_name(0).is_empty());
+        assert_ne!(group_name(0), "0");
+    }
+
+    #[test]
+    fn unknown_ids_fall_back_to_the_number() {
+        assert_eq!(owner_name(3_999_999_999), "3999999999");
+        assert_eq!(group_name(3_999_999_999), "3999999999");
+    }
+
+    #[test]
+    fn each_id_is_looked_up_once() {
+        let cache = Mutex::new(BTreeMap::new());
+        let calls = Cell::new(0);
+        assert_eq!(cached_name(&cache, 7, lookup("me", 0, &calls)), "me");
+        assert_eq!(cached_name(&cache, 7, lookup("other", 0, &calls)), "me");
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cached_name(&cache, 8, lookup("other", 0, &calls)), "other");
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn the_buffer_grows_while_the_lookup_reports_erange() {
+        let cache = Mutex::new(BTreeMap::new());
+        let calls = Cell::new(0);
+        assert_eq!(cached_name(&cache, 1, lookup("me", 5000, &calls)), "me");
+        // 1024, 2048, 4096, then 8192.
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn failed_lookups_give_the_number() {
+        let cache = Mutex::new(BTreeMap::new());
+        let sizes = RefCell::new(Vec::new());
+        let name = cached_name(&cache, 42, |buf| {
+            sizes.borrow_mut().push(buf.len());
+            (libc::ERANGE, ptr::null())
+        });
+        assert_eq!(name, "42");
+        assert_eq!(sizes.borrow().last(), Some(&(1 << 20)));
+        let name = cached_name(&cache, 43, |_| (libc::EIO, ptr::null()));
+        assert_eq!(name, "43");
+        let name = cached_name(&cache, 44, |_| (0, ptr::null()));
+        assert_eq!(name, "44");
+    }
+}
@@ -0,0 +1,397 @@
+// File: src\model.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: In-memory tree model produced by the walker and consumed by the
+//              renderers.
+// License: MIT
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::Counts;
+
+// ── Node kind ─────────────────────────────────────────────────────────────────
+
+/// What kind of filesystem object a [`TreeNode`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Directory,
+    File,
+    Symlink,
+    Fifo,
+    Socket,
+}
+
+impl NodeKind {
+    pub(crate) fn from_file_type(ft: fs::FileType) -> NodeKind {
+        if ft.is_dir() {
+            return NodeKind::Directory;
+        }
+        if ft.is_symlink() {
+            return NodeKind::Symlink;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            if ft.is_fifo() {
+                return NodeKind::Fifo;
+            }
+            if ft.is_socket() {
+                return NodeKind::Socket;
+            }
+        }
+        NodeKind::File
+    }
+
+    pub fn is_dir(self) -> bool {
+        self == NodeKind::Directory
+    }
+}
+
+// ── Metadata ──────────────────────────────────────────────────────────────────
+
+/// The subset of file metadata tree2 can display.
+///
+/// Unix-only values are `None` on other platforms.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeMeta {
+    /// Size in bytes. With `--du` this is the accumulated size for directories.
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Full `st_mode`, including the file type bits.
+    pub mode: Option<u32>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    pub inode: Option<u64>,
+    pub device: Option<u64>,
+}
+
+impl NodeMeta {
+    pub(crate) fn from_metadata(meta: &fs::Metadata) -> NodeMeta {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            NodeMeta {
+                size: meta.len(),
+                modified: meta.modified().ok(),
+                mode: Some(meta.mode()),
+                uid: Some(meta.uid()),
+                gid: Some(meta.gid()),
+                inode: Some(meta.ino()),
+                device: Some(meta.dev()),
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            NodeMeta {
+                size: meta.len(),
+                modified: meta.modified().ok(),
+                ..NodeMeta::default()
+            }
+        }
+    }
+
+    /// Whether any execute bit is set.
+    pub fn is_executable(&self) -> bool {
+        self.mode.is_some_and(|m| m & 0o111 != 0)
+    }
+}
+
+// ── Errors ────────────────────────────────────────────────────────────────────
+
+/// Why a directory node has no children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeError {
+    /// `read_dir` failed, usually for lack of permission.
+    ReadDir {
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The directory holds more entries than `--filelimit` allows.
+    FileLimit(usize),
+    /// A symbolic link followed with `-l` that leads back to one of its own
+    /// parents.
+    Recursive,
+}
+
+impl NodeError {
+    pub(crate) fn read_dir(e: &io::Error) -> NodeError {
+        NodeError::ReadDir {
+            kind: e.kind(),
+            message: e.to_string(),
+        }
+    }
+}
+
+// ── Git status ────────────────────────────────────────────────────────────────
+
+/// Where an entry stands in git, shown with `--git-status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitStatus {
+    /// Changed in the index or the work tree (`M`).
+    Modified,
+    /// New in the index (`A`).
+    Added,
+    /// Deleted from the index or the work tree (`D`). The entry is no
+    /// longer on disk and is listed from the index.
+    Deleted,
+    /// Renamed in the index or the work tree (`R`), under its new name.
+    Renamed,
+    /// Not tracked (`??`).
+    Untracked,
+    /// Matched by git's ignore rules (`!!`).
+    Ignored,
+    /// A directory with changed entries somewhere below it (`*`).
+    Dirty,
+}
+
+impl GitStatus {
+    /// The marker printed in front of the entry, as `git status --short`
+    /// would print it.
+    pub fn marker(self) -> &'static str {
+        match self {
+            GitStatus::Modified => "M",
+            GitStatus::Added => "A",
+            GitStatus::Deleted => "D",
+            GitStatus::Renamed => "R",
+            GitStatus::Untracked => "??",
+            GitStatus::Ignored => "!!",
+            GitStatus::Dirty => "*",
+        }
+    }
+}
+
+// ── Node ──────────────────────────────────────────────────────────────────────
+
+/// One entry of the tree, with its children already filtered and sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    /// File name; the root node carries its full path here.
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: NodeKind,
+    pub metadata: Option<NodeMeta>,
+    /// Target of a symbolic link.
+    pub link_target: Option<PathBuf>,
+    pub children: Vec<TreeNode>,
+    pub error: Option<NodeError>,
+    /// Excluded or matched by an ignore file, and only listed because of
+    /// `--show-ignored`. Everything below an ignored directory is ignored.
+    pub ignored: bool,
+    /// Git status with `--git-status`; `None` for clean entries.
+    pub git: Option<GitStatus>,
+}
+
+impl TreeNode {
+    /// A node without metadata or children.
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>, kind: NodeKind) -> TreeNode {
+        TreeNode {
+            name: name.into(),
+            path: path.into(),
+            kind,
+            metadata: None,
+            link_target: None,
+            children: Vec::new(),
+            error: None,
+            ignored: false,
+            git: None,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind.is_dir()
+    }
+
+    /// Size from the metadata, or 0 when there is none.
+    pub fn size(&self) -> u64 {
+        self.metadata.as_ref().map_or(0, |m| m.size)
+    }
+
+    /// Directories and files below this node (the node itself is not
+    /// counted). Ignored and deleted entries are counted on their own.
+    pub fn counts(&self) -> Counts {
+        let mut counts = Counts::default();
+        for child in &self.children {
+            match (child.is_dir(), child.ignored) {
+                _ if child.git == Some(GitStatus::Deleted) => counts.deleted += 1,
+                (true, false) => counts.dirs += 1,
+                (false, false) => counts.files += 1,
+                (true, true) => counts.ignored_dirs += 1,
+                (false, true) => counts.ignored_files += 1,
+            }
+            let sub = child.counts();
+            counts.dirs += sub.dirs;
+            counts.files += sub.files;
+            counts.ignored_dirs += sub.ignored_dirs;
+            counts.ignored_files += sub.ignored_files;
+            counts.deleted += sub.deleted;
+        }
+        counts
+    }
+
+    /// Drop directories that end up with nothing to show (`--prune`).
+    /// Directories that were not read (depth limit, errors) are kept.
+    pub(crate) fn prune(&mut self, depth: usize, level: Option<usize>) {
+        for child in &mut self.children {
+            if child.is_dir() {
+                child.prune(depth + 1, level);
+            }
+        }
+        let at_limit = |d: usize| level.is_some_and(|max| d >= max);
+        self.children.retain(|c| {
+            !c.is_dir() || !c.children.is_empty() || c.error.is_some() || at_limit(depth)
+        });
+    }
+}
+
+// ── Hidden entries ────────────────────────────────────────────────────────────
+
+/// What leaves an entry out of the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HideReason {
+    /// `--exclude NAME`.
+    Exclude(String),
+    /// A `default_excludes` pattern from the config files.
+    DefaultExclude(String),
+    /// The built-in list of system entries (`.git`, `.DS_Store`, ...),
+    /// shown with `-a`.
+    System(String),
+    /// A rule of an ignore file.
+    IgnoreFile {
+        file: PathBuf,
+        /// 1-based line number.
+        line: usize,
+        rule: String,
+    },
+    /// `-I PATTERN`.
+    IgnorePattern(String),
+    /// A file not matched by `-P PATTERN`.
+    Pattern(String),
+    /// A file under `-d`.
+    DirsOnly,
+    /// The parent directory has `entries` entries, more than `--filelimit`.
+    FileLimit { entries: usize, limit: usize },
+    /// On another filesystem than the root (`--xdev`).
+    OtherDevice,
+    /// Deeper than `-L`.
+    Level(usize),
+}
+
+impl HideReason {
+    /// Whether this is an exclusion (`--exclude`, `default_excludes` or an
+    /// ignore file) that `--show-ignored` lists anyway. The system list is
+    /// not one: `.git` and the like stay hidden unless `-a` is given.
+    pub(crate) fn is_exclusion(&self) -> bool {
+        matches!(
+            self,
+            HideReason::Exclude(_) | HideReason::DefaultExclude(_) | HideReason::IgnoreFile { .. }
+        )
+    }
+
+    /// Whether this is `-d` or `-P`, which only choose the files that are
+    /// printed: `--du` still counts them.
+    pub(crate) fn is_listing_filter(&self) -> bool {
+        matches!(self, HideReason::DirsOnly | HideReason::Pattern(_))
+    }
+}
+
+impl fmt::Display for HideReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HideReason::Exclude(name) => write!(f, "--exclude {}", name),
+            HideReason::DefaultExclude(pattern) => {
+                write!(f, "default_excludes pattern '{}' (config file)", pattern)
+            }
+            HideReason::System(name) => {
+                write!(f, "built-in system exclude '{}' (shown with -a)", name)
+            }
+            HideReason::IgnoreFile { file, line, rule } => {
+                write!(f, "'{}' at {}:{}", rule, file.display(), line)
+            }
+            HideReason::IgnorePattern(pattern) => write!(f, "-I '{}'", pattern),
+            HideReason::Pattern(pattern) => write!(f, "not matched by -P '{}'", pattern),
+            HideReason::DirsOnly => write!(f, "-d (not a directory)"),
+            HideReason::FileLimit { entries, limit } => write!(
+                f,
+                "--filelimit {} (its directory has {} entries)",
+                limit, entries
+            ),
+            HideReason::OtherDevice => write!(f, "--xdev (on another filesystem)"),
+            HideReason::Level(max) => write!(f, "-L {} (too deep)", max),
+        }
+    }
+}
+
+/// Why an entry is or is not in the tree; see [`crate::explain`] and
+/// [`crate::Tree::hidden`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// Path relative to the root of the tree.
+    pub path: PathBuf,
+    /// What hides the entry; `None` when it is listed.
+    pub hidden_by: Option<HideReason>,
+    /// An `--exception` pattern that kept the entry, with what it overrode.
+    pub exception: Option<(String, HideReason)>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path.display())?;
+        match self.hidden_by {
+            Some(ref reason) => write!(f, "hidden by {}", reason)?,
+            None => f.write_str("shown")?,
+        }
+        if let Some((ref pattern, ref overridden)) = self.exception {
+            write!(
+                f,
+                "{}--exception '{}' overrides {}",
+                if self.hidden_by.is_some() {
+                    ", although "
+                } else {
+                    ", "
+                },
+                pattern,
+                overridden
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, git: Option<GitStatus>) -> TreeNode {
+        let mut node = TreeNode::new(name, name, NodeKind::File);
+        node.git = git;
+        node
+    }
+
+    #[test]
+    fn deleted_entries_are_counted_on_their_own() {
+        let mut root = TreeNode::new("root", "root", NodeKind::Directory);
+        let mut gone = TreeNode::new("gone", "root/gone", NodeKind::Directory);
+        gone.git = Some(GitStatus::Deleted);
+        gone.children.push(file("old.rs", Some(GitStatus::Deleted)));
+        let mut src = TreeNode::new("src", "root/src", NodeKind::Directory);
+        src.children
+            .push(file("main.rs", Some(GitStatus::Modified)));
+        src.children.push(file("lib.rs", Some(GitStatus::Deleted)));
+        root.children = vec![gone, src, file("README.md", None)];
+
+        let counts = root.counts();
+        assert_eq!((counts.dirs, counts.files, counts.deleted), (1, 2, 3));
+        assert_eq!(counts.report(), "\n1 directory, 2 files (3 deleted)\n");
+    }
+
+    #[test]
+    fn report_without_deleted_entries_is_unchanged() {
+        let mut root = TreeNode::new("root", "root", NodeKind::Directory);
+        root.children = vec![file("a", None), file("b", Some(GitStatus::Untracked))];
+        assert_eq!(root.counts().report(), "\n0 directories, 2 files\n");
+    }
+}
@@ -0,0 +1,163 @@
+// File: src\pathtree.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Trees built from a list of paths (the git index, untracked
+//              files) instead of a directory walk, with the same filters.
+// License: MIT
+
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::ignore::IgnoreStack;
+use crate::model::{Explanation, HideReason, NodeError, NodeKind, NodeMeta, TreeNode};
+use crate::walk::{admit, set_du_size, sort_nodes};
+
+/// One listed entry, relative to the root of the tree.
+#[derive(Debug, Clone)]
+pub(crate) struct PathEntry {
+    pub(crate) path: PathBuf,
+    pub(crate) kind: NodeKind,
+    pub(crate) metadata: Option<NodeMeta>,
+    pub(crate) link_target: Option<PathBuf>,
+}
+
+/// Put `entries` under `root` and run the walk's filters over them.
+/// Directories that are only implied by the paths get their metadata from
+/// `dir_meta`. Ignore files are not read: the listing already decided what
+/// belongs in the tree.
+pub(crate) fn build_path_tree(
+    root: &mut TreeNode,
+    mut entries: Vec<PathEntry>,
+    config: &Config,
+    hidden: &mut Vec<Explanation>,
+    dir_meta: impl Fn(&Path) -> Option<NodeMeta>,
+) {
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    for entry in entries {
+        insert(root, entry, &dir_meta);
+    }
+    let no_ignores = IgnoreStack::new(Vec::new());
+    filter_children(root, config, &no_ignores, 1, hidden);
+}
+
+/// Add `entry` below `root`, creating the directories on its path. With
+/// sorted input the directory to descend into is always the last child.
+fn insert(root: &mut TreeNode, entry: PathEntry, dir_meta: &impl Fn(&Path) -> Option<NodeMeta>) {
+    let names: Vec<String> = entry
+        .path
+        .iter()
+        .map(|c| c.to_string_lossy().to_string())
+        .collect();
+    let Some((leaf, dirs)) = names.split_last() else {
+        return;
+    };
+    let mut node = root;
+    for name in dirs {
+        let found = node
+            .children
+            .iter()
+            .rposition(|c| c.is_dir() && &c.name == name);
+        let idx = match found {
+            Some(idx) => idx,
+            None => {
+                let path = node.path.join(name);
+                let mut dir = TreeNode::new(name.as_str(), &path, NodeKind::Directory);
+                dir.metadata = dir_meta(&path);
+                node.children.push(dir);
+                node.children.len() - 1
+            }
+        };
+        node = &mut node.children[idx];
+    }
+    let mut leaf_node = TreeNode::new(leaf.as_str(), node.path.join(leaf), entry.kind);
+    leaf_node.metadata = entry.metadata;
+    leaf_node.link_target = entry.link_target;
+    node.children.push(leaf_node);
+}
+
+/// The counterpart of the walk's `read_children` for a tree that is
+/// already built: drop what the filters hide, recurse, then sort. With
+/// `--du`, what lies below `-L` or `--filelimit` is filtered the same way
+/// for its size before it is dropped.
+fn filter_children(
+    node: &mut TreeNode,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    depth: usize,
+    hidden: &mut Vec<Explanation>,
+) {
+    let listed = config.level.is_none_or(|max| depth <= max);
+    if !listed && !config.du {
+        node.children.clear();
+        return;
+    }
+    if let Some(limit) = config.filelimit.filter(|_| listed) {
+        let entries = node.children.len();
+        if entries > limit {
+            node.error = Some(NodeError::FileLimit(entries));
+            if config.why {
+                let reason = HideReason::FileLimit { entries, limit };
+                hidden.extend(node.children.iter().map(|c| Explanation {
+                    path: config.relative(&c.path),
+                    hidden_by: Some(reason.clone()),
+                    exception: None,
+                }));
+            }
+            if !config.du {
+                node.children.clear();
+                return;
+            }
+        }
+    }
+    let keep = listed && node.error.is_none();
+    let mut scratch = Vec::new();
+    let hidden = if keep { hidden } else { &mut scratch };
+
+    let mut children = Vec::with_capacity(node.children.len());
+    let mut skipped = 0;
+    for mut child in mem::take(&mut node.children) {
+        let device = child.metadata.as_ref().and_then(|m| m.device);
+        match admit(
+            &child.name,
+            &child.path,
+            child.is_dir(),
+            device,
+            config,
+            ignores,
+            hidden,
+        ) {
+            Ok(ignored) => {
+                child.ignored = ignored || node.ignored;
+                children.push(child);
+            }
+            Err(reason) if config.du_all || (config.du && reason.is_listing_filter()) => {
+                skipped += sum_sizes(&mut child)
+            }
+            Err(_) => {}
+        }
+    }
+    for child in &mut children {
+        if child.is_dir() {
+            filter_children(child, config, ignores, depth + 1, hidden);
+        }
+    }
+    if config.du {
+        set_du_size(node, &children, skipped, config);
+    }
+    if keep {
+        sort_nodes(&mut children, config);
+        node.children = children;
+    }
+}
+
+/// `--du`: the size of a hidden entry, with everything listed below
+/// it whatever the filters say.
+fn sum_sizes(node: &mut TreeNode) -> u64 {
+    if !node.is_dir() {
+        return node.size();
+    }
+    let total = node.children.iter_mut().map(sum_sizes).sum();
+    node.metadata.get_or_insert_with(NodeMeta::default).size = total;
+    total
+}
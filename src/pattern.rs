@@ -0,0 +1,96 @@
+// File: src\pattern.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Wildcard, regex and exact-match patterns used by the filters.
+// License: MIT
+
+use regex::Regex;
+
+// ── Wildcard pattern (-P / -I) ────────────────────────────────────────────────
+
+/// A `*`/`?` wildcard used by `-P` and `-I`, optionally case-insensitive.
+pub(crate) struct WildPattern {
+    raw: String,
+    ignore_case: bool,
+}
+
+impl WildPattern {
+    pub(crate) fn new(s: &str, ignore_case: bool) -> Self {
+        WildPattern {
+            raw: s.to_string(),
+            ignore_case,
+        }
+    }
+
+    /// The pattern as it was given.
+    pub(crate) fn source(&self) -> &str {
+        &self.raw
+    }
+
+    pub(crate) fn matches(&self, text: &str) -> bool {
+        let (pattern, haystack) = if self.ignore_case {
+            (self.raw.to_lowercase(), text.to_lowercase())
+        } else {
+            (self.raw.clone(), text.to_string())
+        };
+        wildcard_match(&pattern, &haystack)
+    }
+}
+
+// ── Exception pattern (--exception) ──────────────────────────────────────────
+
+pub(crate) enum Pattern {
+    Wildcard(String),
+    Regex(Regex),
+    Exact(String),
+}
+
+impl Pattern {
+    pub(crate) fn matches(&self, text: &str) -> bool {
+        match self {
+            Pattern::Wildcard(pattern) => wildcard_match(pattern, text),
+            Pattern::Regex(re) => re.is_match(text),
+            Pattern::Exact(exact) => text == exact,
+        }
+    }
+
+    /// The pattern as it was given.
+    pub(crate) fn source(&self) -> String {
+        match self {
+            Pattern::Wildcard(pattern) | Pattern::Exact(pattern) => pattern.clone(),
+            Pattern::Regex(re) => format!("regex:{}", re.as_str()),
+        }
+    }
+
+    pub(crate) fn from_string(s: &str) -> Result<Self, String> {
+        if let Some(pattern) = s.strip_prefix("regex:") {
+            match Regex::new(pattern) {
+                Ok(re) => Ok(Pattern::Regex(re)),
+                Err(e) => Err(format!("Invalid regex '{}': {}", pattern, e)),
+            }
+        } else if s.contains('*') || s.contains('?') {
+            Ok(Pattern::Wildcard(s.to_string()))
+        } else {
+            Ok(Pattern::Exact(s.to_string()))
+        }
+    }
+}
+
+// ── Wildcard matcher ──────────────────────────────────────────────────────────
+
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pc: Vec<char> = pattern.chars().collect();
+    let tc: Vec<char> = text.chars().collect();
+    wm_rec(&pc, &tc, 0, 0)
+}
+
+fn wm_rec(pattern: &[char], text: &[char], pi: usize, ti: usize) -> bool {
+    if pi == pattern.len() {
+        return ti == text.len();
+    }
+    match pattern[pi] {
+        '*' => (ti..=text.len()).any(|i| wm_rec(pattern, text, pi + 1, i)),
+        '?' => ti < text.len() && wm_rec(pattern, text, pi + 1, ti + 1),
+        c => ti < text.len() && text[ti] == c && wm_rec(pattern, text, pi + 1, ti + 1),
+    }
+}
@@ -0,0 +1,279 @@
+// File: src\render\html.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Self-contained HTML page with collapsible directories (-H).
+// License: MIT
+
+use std::fmt::{self, Write};
+use std::path::Path;
+
+use super::{
+    build_meta_prefix, dir_meta_prefix, display_name, error_message, markup_escape, relative_href,
+    size_parts, structured_name,
+};
+use crate::meta::classify_indicator;
+use crate::model::{GitStatus, NodeKind, TreeNode};
+use crate::TreeConfig;
+
+/// The terminal color scheme as CSS (see the color constants in color.rs).
+const STYLE: &str = "\
+body { background: #1e1e1e; color: #e6e6e6; font-family: ui-monospace, Menlo, Consolas, monospace; }
+ul { list-style: none; margin: 0; padding-left: 1.6em; border-left: 1px dotted #555; }
+summary { cursor: pointer; }
+a { text-decoration: none; }
+a:hover { text-decoration: underline; }
+.dir { color: #FFFF00; }
+.file { color: #00FFFF; }
+.link { color: #00FF80; }
+.size { color: #FF80FF; }
+.zero, .error { color: #FFFFFF; background: #C00000; font-weight: bold; }
+.unit { color: #FFB380; }
+.meta { color: #A0A0A0; }
+.report { color: #E6E6E6; margin-top: 1em; }
+.git { display: inline-block; width: 2.5ch; font-weight: bold; }
+.git-modified, .git-dirty { color: #FFAF00; }
+.git-added { color: #00FF80; }
+.git-deleted { color: #FFFFFF; background: #C00000; }
+.git-renamed { color: #FF80FF; }
+.git-untracked { color: #FF5555; }
+.git-ignored { color: #A0A0A0; }
+.ignored > summary > span, .ignored > a, .ignored > span { opacity: 0.5; text-decoration: line-through; }
+.ignored .tag { opacity: 0.5; text-decoration: none; color: #A0A0A0; }
+";
+
+/// Render a complete HTML document. Files link to `base_href` followed by
+/// their path relative to the root.
+pub(crate) fn render_html(
+    out: &mut dyn Write,
+    root: &TreeNode,
+    opts: &TreeConfig,
+    base_href: &str,
+) -> fmt::Result {
+    // The path as given, like JSON and XML: a published page should not
+    // show where the tree was on the machine that rendered it.
+    let name = structured_name(root, opts, true);
+    let title = markup_escape(&name);
+    out.write_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n")?;
+    writeln!(out, "<title>{}</title>", title)?;
+    writeln!(out, "<style>\n{}</style>", STYLE)?;
+    out.write_str("</head>\n<body>\n")?;
+
+    writeln!(
+        out,
+        "<details open><summary><span class=\"dir\">📂 {}/</span></summary>",
+        markup_escape(name.trim_end_matches('/'))
+    )?;
+    let ctx = HtmlCtx {
+        root: &root.path,
+        base_href,
+        opts,
+    };
+    write_children(out, root, &ctx)?;
+    out.write_str("</details>\n")?;
+
+    if !opts.noreport {
+        writeln!(
+            out,
+            "<p class=\"report\">{}</p>",
+            root.counts().report().trim()
+        )?;
+    }
+    out.write_str("</body>\n</html>\n")
+}
+
+struct HtmlCtx<'a> {
+    root: &'a Path,
+    base_href: &'a str,
+    opts: &'a TreeConfig,
+}
+
+fn write_children(out: &mut dyn Write, node: &TreeNode, ctx: &HtmlCtx<'_>) -> fmt::Result {
+    if node.error.is_none() && node.children.is_empty() {
+        return Ok(());
+    }
+    out.write_str("<ul>\n")?;
+    if let Some(ref err) = node.error {
+        writeln!(
+            out,
+            "<li><span class=\"error\">🔒 [{}]</span></li>",
+            markup_escape(&error_message(err))
+        )?;
+    }
+    for child in &node.children {
+        out.write_str(if child.ignored {
+            "<li class=\"ignored\">"
+        } else {
+            "<li>"
+        })?;
+        if child.is_dir() {
+            out.write_str("<details open><summary>")?;
+            write_dir_label(out, child, ctx.opts)?;
+            write_ignored_tag(out, child)?;
+            out.write_str("</summary>\n")?;
+            write_children(out, child, ctx)?;
+            out.write_str("</details>")?;
+        } else {
+            write_file_label(out, child, ctx)?;
+            write_ignored_tag(out, child)?;
+        }
+        out.write_str("</li>\n")?;
+    }
+    out.write_str("</ul>\n")
+}
+
+fn write_dir_label(out: &mut dyn Write, node: &TreeNode, opts: &TreeConfig) -> fmt::Result {
+    write_git_marker(out, node, opts)?;
+    let meta_str = dir_meta_prefix(node, opts);
+    if !meta_str.is_empty() {
+        write!(
+            out,
+            "<span class=\"meta\">{}</span>",
+            markup_escape(&meta_str)
+        )?;
+    }
+    let indicator = if opts.classify { "/" } else { "" };
+    write!(
+        out,
+        "<span class=\"dir\">📁 {}{}/</span>",
+        markup_escape(&display_name(node, opts)),
+        indicator
+    )?;
+    if let Some(ref target) = node.link_target {
+        write!(
+            out,
+            "<span class=\"link\"> -&gt; {}</span>",
+            markup_escape(&target.to_string_lossy())
+        )?;
+    }
+    Ok(())
+}
+
+fn write_file_label(out: &mut dyn Write, node: &TreeNode, ctx: &HtmlCtx<'_>) -> fmt::Result {
+    let opts = ctx.opts;
+    write_git_marker(out, node, opts)?;
+    let meta_str = build_meta_prefix(node, opts);
+    if !meta_str.is_empty() {
+        write!(
+            out,
+            "<span class=\"meta\">{}</span>",
+            markup_escape(&meta_str)
+        )?;
+    }
+    let class = if node.kind == NodeKind::Symlink {
+        "link"
+    } else {
+        "file"
+    };
+    let indicator = if opts.classify {
+        classify_indicator(node.kind, node.metadata.as_ref())
+    } else {
+        ""
+    };
+    write!(
+        out,
+        "<a class=\"{}\" href=\"{}\">📄 {}{}</a>",
+        class,
+        markup_escape(&file_href(ctx.base_href, ctx.root, &node.path)),
+        markup_escape(&display_name(node, opts)),
+        markup_escape(indicator)
+    )?;
+
+    let sz = node.size();
+    let (size_val, size_unit) = size_parts(sz, opts);
+    let val_class = if sz == 0 { "zero" } else { "size" };
+    write!(
+        out,
+        " (<span class=\"{}\">{}</span> <span class=\"unit\">{}</span>)",
+        val_class, size_val, size_unit
+    )?;
+    if let Some(ref target) = node.link_target {
+        write!(
+            out,
+            "<span class=\"link\"> -&gt; {}</span>",
+            markup_escape(&target.to_string_lossy())
+        )?;
+    }
+    Ok(())
+}
+
+/// The `--git-status` marker; clean entries get an empty cell so that
+/// names stay aligned.
+fn write_git_marker(out: &mut dyn Write, node: &TreeNode, opts: &TreeConfig) -> fmt::Result {
+    if !opts.git_status {
+        return Ok(());
+    }
+    match node.git {
+        Some(status) => write!(
+            out,
+            "<span class=\"git git-{}\">{}</span>",
+            git_class(status),
+            status.marker()
+        ),
+        None => out.write_str("<span class=\"git\"></span>"),
+    }
+}
+
+fn git_class(status: GitStatus) -> &'static str {
+    match status {
+        GitStatus::Modified => "modified",
+        GitStatus::Added => "added",
+        GitStatus::Deleted => "deleted",
+        GitStatus::Renamed => "renamed",
+        GitStatus::Untracked => "untracked",
+        GitStatus::Ignored => "ignored",
+        GitStatus::Dirty => "dirty",
+    }
+}
+
+fn write_ignored_tag(out: &mut dyn Write, node: &TreeNode) -> fmt::Result {
+    if node.ignored {
+        out.write_str(" <span class=\"tag\">[ignored]</span>")?;
+    }
+    Ok(())
+}
+
+/// `base_href` joined with the path of `path` below `root`.
+fn file_href(base_href: &str, root: &Path, path: &Path) -> String {
+    let mut href = base_href.to_string();
+    if !href.is_empty() && !href.ends_with('/') {
+        href.push('/');
+    }
+    href.push_str(&relative_href(root, path));
+    href
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(path: &str) -> String {
+        let mut root = TreeNode::new("/home/me/project", "/home/me/project", NodeKind::Directory);
+        root.children.push(TreeNode::new(
+            "a.txt",
+            "/home/me/project/a.txt",
+            NodeKind::File,
+        ));
+        let opts = TreeConfig {
+            path: path.into(),
+            ..TreeConfig::default()
+        };
+        let mut out = String::new();
+        render_html(&mut out, &root, &opts, "").unwrap();
+        out
+    }
+
+    #[test]
+    fn page_is_titled_by_the_path_as_given() {
+        let out = page(".");
+        assert!(out.contains("<title>.</title>"));
+        assert!(out.contains("<span class=\"dir\">📂 ./</span>"));
+        assert!(!out.contains("/home/me"));
+    }
+
+    #[test]
+    fn title_is_escaped_and_the_root_has_one_slash() {
+        let out = page("docs & more/");
+        assert!(out.contains("<title>docs &amp; more/</title>"));
+        assert!(out.contains("<span class=\"dir\">📂 docs &amp; more/</span>"));
+    }
+}
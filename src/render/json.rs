@@ -0,0 +1,112 @@
+// File: src\render\json.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: JSON output laid out like GNU `tree -J`.
+// License: MIT
+
+use std::fmt::{self, Write};
+
+use super::{error_message, meta_fields, structured_name, type_name, FieldValue};
+use crate::model::TreeNode;
+use crate::TreeConfig;
+
+/// Render the tree as a JSON array: the root directory followed by the
+/// report object (unless `noreport`).
+pub(crate) fn render_json(out: &mut dyn Write, root: &TreeNode, opts: &TreeConfig) -> fmt::Result {
+    out.write_str("[\n")?;
+    write_node(out, root, opts, 1)?;
+    if !opts.noreport {
+        let counts = root.counts();
+        out.write_str("\n,\n")?;
+        write!(
+            out,
+            "  {{\"type\":\"report\",\"directories\":{},\"files\":{}",
+            counts.dirs, counts.files
+        )?;
+        if opts.show_ignored {
+            write!(
+                out,
+                ",\"ignored_directories\":{},\"ignored_files\":{}",
+                counts.ignored_dirs, counts.ignored_files
+            )?;
+        }
+        if opts.git_status {
+            write!(out, ",\"deleted\":{}", counts.deleted)?;
+        }
+        if opts.du {
+            write!(out, ",\"size\":{}", root.size())?;
+        }
+        out.write_str("}")?;
+    }
+    out.write_str("\n]\n")
+}
+
+fn write_node(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    opts: &TreeConfig,
+    depth: usize,
+) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    let name = structured_name(node, opts, depth == 1);
+    write!(
+        out,
+        "{}{{\"type\":\"{}\",\"name\":\"{}\"",
+        indent,
+        type_name(node),
+        json_escape(&name)
+    )?;
+    for (key, value) in meta_fields(node, opts) {
+        match value {
+            FieldValue::Num(n) => write!(out, ",\"{}\":{}", key, n)?,
+            FieldValue::Str(s) => write!(out, ",\"{}\":\"{}\"", key, json_escape(&s))?,
+        }
+    }
+    if let Some(ref target) = node.link_target {
+        write!(
+            out,
+            ",\"target\":\"{}\"",
+            json_escape(&target.to_string_lossy())
+        )?;
+    }
+    if node.ignored {
+        out.write_str(",\"ignored\":true")?;
+    }
+
+    if !node.is_dir() {
+        return out.write_str("}");
+    }
+    if let Some(ref err) = node.error {
+        write!(out, ",\"error\":\"{}\"", json_escape(&error_message(err)))?;
+    }
+    if node.children.is_empty() {
+        return out.write_str(",\"contents\":[]}");
+    }
+    out.write_str(",\"contents\":[\n")?;
+    for (idx, child) in node.children.iter().enumerate() {
+        write_node(out, child, opts, depth + 1)?;
+        if idx + 1 < node.children.len() {
+            out.write_str(",")?;
+        }
+        out.write_str("\n")?;
+    }
+    write!(out, "{}]}}", indent)
+}
+
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
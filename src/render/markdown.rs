@@ -0,0 +1,219 @@
+// File: src\render\markdown.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Markdown output for READMEs and docs: a fenced code block or a
+//              nested list of relative links (--format markdown[-list]).
+// License: MIT
+
+use std::fmt::{self, Write};
+
+use super::{
+    build_meta_prefix, dir_meta_prefix, display_name, error_message, git_column, relative_href,
+};
+use crate::meta::classify_indicator;
+use crate::model::TreeNode;
+use crate::{MarkdownStyle, TreeConfig};
+
+/// Render the tree as Markdown in the given style. Metadata columns only
+/// appear when the matching option is enabled.
+pub(crate) fn render_markdown(
+    out: &mut dyn Write,
+    root: &TreeNode,
+    opts: &TreeConfig,
+    style: MarkdownStyle,
+) -> fmt::Result {
+    match style {
+        MarkdownStyle::CodeBlock => {
+            let mut body = String::new();
+            write_code_body(&mut body, root, opts)?;
+            let fence = "`".repeat(longest_backtick_run(&body).max(2) + 1);
+            writeln!(out, "{}text", fence)?;
+            out.write_str(&body)?;
+            writeln!(out, "{}", fence)
+        }
+        MarkdownStyle::List => {
+            writeln!(out, "- [{}/](./)", md_escape(&root_label(root)))?;
+            write_list(out, root, root, "  ", opts)?;
+            if !opts.noreport {
+                out.write_str(&root.counts().report())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The root is labelled with its directory name rather than the absolute
+/// path, which means nothing to readers of the document.
+fn root_label(root: &TreeNode) -> String {
+    root.path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.name.clone())
+}
+
+// ── Code block ────────────────────────────────────────────────────────────────
+
+fn write_code_body(out: &mut String, root: &TreeNode, opts: &TreeConfig) -> fmt::Result {
+    writeln!(out, "{}/", root_label(root))?;
+    write_code_children(out, root, "", opts)?;
+    if !opts.noreport {
+        out.push_str(&root.counts().report());
+    }
+    Ok(())
+}
+
+fn write_code_children(
+    out: &mut String,
+    node: &TreeNode,
+    prefix: &str,
+    opts: &TreeConfig,
+) -> fmt::Result {
+    if let Some(ref err) = node.error {
+        writeln!(out, "{}└── [{}]", prefix, error_message(err))?;
+        return Ok(());
+    }
+    for (idx, child) in node.children.iter().enumerate() {
+        let last = idx == node.children.len() - 1;
+        let connector = if last { "└── " } else { "├── " };
+        writeln!(out, "{}{}{}", prefix, connector, entry_text(child, opts))?;
+        if child.is_dir() {
+            let new_prefix = if last {
+                format!("{}    ", prefix)
+            } else {
+                format!("{}│   ", prefix)
+            };
+            write_code_children(out, child, &new_prefix, opts)?;
+        }
+    }
+    Ok(())
+}
+
+/// One code block line without the connector: git marker, metadata, name,
+/// indicator and symlink target.
+fn entry_text(node: &TreeNode, opts: &TreeConfig) -> String {
+    let (meta_str, indicator) = meta_and_indicator(node, opts);
+    let mut line = format!(
+        "{}{}{}{}",
+        git_column(node, opts),
+        meta_str,
+        display_name(node, opts),
+        indicator
+    );
+    if let Some(ref target) = node.link_target {
+        let _ = write!(line, " -> {}", target.display());
+    }
+    if node.ignored {
+        line.push_str(" [ignored]");
+    }
+    line
+}
+
+fn longest_backtick_run(s: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in s.chars() {
+        if c == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    longest
+}
+
+// ── Link list ─────────────────────────────────────────────────────────────────
+
+fn write_list(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    root: &TreeNode,
+    indent: &str,
+    opts: &TreeConfig,
+) -> fmt::Result {
+    if let Some(ref err) = node.error {
+        return writeln!(out, "{}- *{}*", indent, md_escape(&error_message(err)));
+    }
+    let deeper = format!("{}  ", indent);
+    for child in &node.children {
+        let (meta_str, indicator) = meta_and_indicator(child, opts);
+        let mut href = relative_href(&root.path, &child.path);
+        if child.is_dir() {
+            href.push('/');
+        }
+        // Ignored entries are struck through and tagged.
+        let strike = if child.ignored { "~~" } else { "" };
+        write!(out, "{}- ", indent)?;
+        if let Some(status) = child.git.filter(|_| opts.git_status) {
+            write!(out, "{} ", inline_code(status.marker()))?;
+        }
+        write!(
+            out,
+            "{}[{}{}]({}){}",
+            strike,
+            md_escape(&display_name(child, opts)),
+            md_escape(indicator),
+            href,
+            strike
+        )?;
+        if let Some(ref target) = child.link_target {
+            write!(out, " → {}", inline_code(&target.to_string_lossy()))?;
+        }
+        let meta_str = meta_str.trim_end();
+        if !meta_str.is_empty() {
+            write!(out, " {}", inline_code(meta_str))?;
+        }
+        if child.ignored {
+            out.write_str(" \\[ignored\\]")?;
+        }
+        out.write_str("\n")?;
+        if child.is_dir() {
+            write_list(out, child, root, &deeper, opts)?;
+        }
+    }
+    Ok(())
+}
+
+/// Backslash-escape the characters that would otherwise start Markdown
+/// syntax inside link text.
+fn md_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '(' | ')' | '#' | '!' | '|' | '~'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// `s` as an inline code span, using a longer backtick fence when `s`
+/// contains backticks itself.
+fn inline_code(s: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(s) + 1);
+    if s.starts_with('`') || s.ends_with('`') {
+        format!("{} {} {}", fence, s, fence)
+    } else {
+        format!("{}{}{}", fence, s, fence)
+    }
+}
+
+// ── Shared ────────────────────────────────────────────────────────────────────
+
+/// Metadata prefix (with trailing space) and name suffix for a node:
+/// directories always end in `/`, other entries get the `-F` indicator.
+fn meta_and_indicator(node: &TreeNode, opts: &TreeConfig) -> (String, &'static str) {
+    if node.is_dir() {
+        (dir_meta_prefix(node, opts), "/")
+    } else {
+        let indicator = if opts.classify {
+            classify_indicator(node.kind, node.metadata.as_ref())
+        } else {
+            ""
+        };
+        (build_meta_prefix(node, opts), indicator)
+    }
+}
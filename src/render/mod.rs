@@ -0,0 +1,500 @@
+// File: src\render\mod.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Renderers turning a TreeNode model into output, plus the
+//              metadata formatting they share.
+// License: MIT
+
+use std::fmt::{self, Write};
+use std::io;
+use std::path::Path;
+
+use crate::meta::{format_mtime, human_size, human_size_si, permission_string, sanitize_name};
+#[cfg(unix)]
+use crate::meta::{group_name, owner_name};
+use crate::model::{GitStatus, NodeError, NodeKind, TreeNode};
+use crate::TreeConfig;
+
+pub(crate) mod html;
+pub(crate) mod json;
+pub(crate) mod markdown;
+pub(crate) mod text;
+pub(crate) mod xml;
+
+// ── Writers ───────────────────────────────────────────────────────────────────
+
+/// Run a renderer straight into `out`, then flush it. The renderers write
+/// through `fmt::Write`, which cannot carry an I/O error, so the first one
+/// is kept aside and returned in place of the bare `fmt::Error`.
+pub(crate) fn write_io(
+    out: &mut dyn io::Write,
+    render: impl FnOnce(&mut dyn Write) -> fmt::Result,
+) -> io::Result<()> {
+    struct Adapter<'a> {
+        out: &'a mut dyn io::Write,
+        error: Option<io::Error>,
+    }
+
+    impl Write for Adapter<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.out.write_all(s.as_bytes()).map_err(|e| {
+                self.error = Some(e);
+                fmt::Error
+            })
+        }
+    }
+
+    let mut adapter = Adapter { out, error: None };
+    match render(&mut adapter) {
+        Ok(()) => adapter.out.flush(),
+        Err(_) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::other("formatting failed"))),
+    }
+}
+
+// ── Shared formatting ─────────────────────────────────────────────────────────
+
+/// Name as displayed: the full path with `-f`, otherwise the sanitized name.
+pub(crate) fn display_name(node: &TreeNode, opts: &TreeConfig) -> String {
+    if opts.full_path {
+        node.path.to_string_lossy().to_string()
+    } else {
+        sanitize_name(&node.name, opts.quote_chars, opts.quote)
+    }
+}
+
+/// The metadata columns requested by `-p -u -g -s -h --si -D --inodes
+/// --device`, space separated with a trailing space, or empty.
+pub(crate) fn build_meta_prefix(node: &TreeNode, opts: &TreeConfig) -> String {
+    let meta = match node.metadata {
+        Some(ref m) => m,
+        None => return String::new(),
+    };
+    let mut parts = Vec::new();
+
+    if opts.inodes {
+        if let Some(ino) = meta.inode {
+            parts.push(format!("{}", ino));
+        }
+    }
+    if opts.device {
+        if let Some(dev) = meta.device {
+            parts.push(format!("{}", dev));
+        }
+    }
+    if opts.protections {
+        parts.push(permission_string(node.kind, meta.mode));
+    }
+    if opts.owner {
+        #[cfg(unix)]
+        if let Some(uid) = meta.uid {
+            parts.push(owner_text(uid, opts));
+        }
+    }
+    if opts.group {
+        #[cfg(unix)]
+        if let Some(gid) = meta.gid {
+            parts.push(group_text(gid, opts));
+        }
+    }
+    if opts.size_bytes {
+        parts.push(format!("{}", meta.size));
+    } else if opts.si_units {
+        let sz = human_size_si(meta.size);
+        parts.push(format!("[{}]", sz));
+    } else if opts.human_readable {
+        let sz = human_size(meta.size);
+        parts.push(format!("[{}]", sz));
+    }
+    if opts.date {
+        parts.push(format_mtime(meta.modified));
+    }
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", parts.join(" "))
+    }
+}
+
+/// The `--git-status` column: the marker padded to two characters and a
+/// space, blank for clean entries so that names stay aligned.
+pub(crate) fn git_column(node: &TreeNode, opts: &TreeConfig) -> String {
+    if !opts.git_status {
+        return String::new();
+    }
+    format!("{:<2} ", node.git.map_or("", GitStatus::marker))
+}
+
+/// `-u` column: the owner's name, or the uid with `--numeric-uid-gid`.
+#[cfg(unix)]
+fn owner_text(uid: u32, opts: &TreeConfig) -> String {
+    if opts.numeric_uid_gid {
+        uid.to_string()
+    } else {
+        owner_name(uid)
+    }
+}
+
+/// `-g` column, like [`owner_text`].
+#[cfg(unix)]
+fn group_text(gid: u32, opts: &TreeConfig) -> String {
+    if opts.numeric_uid_gid {
+        gid.to_string()
+    } else {
+        group_name(gid)
+    }
+}
+
+/// Prefix for a directory line: the accumulated size with `--du`, otherwise
+/// the usual metadata columns.
+pub(crate) fn dir_meta_prefix(node: &TreeNode, opts: &TreeConfig) -> String {
+    if opts.du {
+        let s = if opts.si_units {
+            human_size_si(node.size())
+        } else {
+            human_size(node.size())
+        };
+        format!("[{}] ", s)
+    } else {
+        build_meta_prefix(node, opts)
+    }
+}
+
+/// File size split into value and unit, e.g. `("12.45", "KB")`.
+pub(crate) fn size_parts(size: u64, opts: &TreeConfig) -> (String, String) {
+    let size_display = if opts.size_bytes {
+        format!("{} B", size)
+    } else if opts.si_units {
+        human_size_si(size)
+    } else {
+        human_size(size)
+    };
+    let mut parts = size_display.split_whitespace();
+    let val = parts.next().unwrap_or_default().to_string();
+    let unit = parts.next().unwrap_or_default().to_string();
+    (val, unit)
+}
+
+// ── Structured output fields (JSON / XML) ─────────────────────────────────────
+
+pub(crate) enum FieldValue {
+    Num(u64),
+    Str(String),
+}
+
+/// Metadata as GNU tree names it in `-J`/`-X` output, for the columns that
+/// were requested, plus the `git` marker with `--git-status`.
+pub(crate) fn meta_fields(node: &TreeNode, opts: &TreeConfig) -> Vec<(&'static str, FieldValue)> {
+    let mut fields = Vec::new();
+    if opts.git_status {
+        if let Some(status) = node.git {
+            fields.push(("git", FieldValue::Str(status.marker().to_string())));
+        }
+    }
+    let meta = match node.metadata {
+        Some(ref m) => m,
+        None => return fields,
+    };
+    if opts.inodes {
+        if let Some(ino) = meta.inode {
+            fields.push(("inode", FieldValue::Num(ino)));
+        }
+    }
+    if opts.device {
+        if let Some(dev) = meta.device {
+            fields.push(("dev", FieldValue::Num(dev)));
+        }
+    }
+    if opts.protections {
+        if let Some(mode) = meta.mode {
+            fields.push(("mode", FieldValue::Str(format!("{:04o}", mode & 0o7777))));
+        }
+        let prot = permission_string(node.kind, meta.mode);
+        fields.push(("prot", FieldValue::Str(strip_brackets(&prot))));
+    }
+    if opts.owner {
+        #[cfg(unix)]
+        if let Some(uid) = meta.uid {
+            fields.push(("user", FieldValue::Str(owner_text(uid, opts))));
+        }
+    }
+    if opts.group {
+        #[cfg(unix)]
+        if let Some(gid) = meta.gid {
+            fields.push(("group", FieldValue::Str(group_text(gid, opts))));
+        }
+    }
+    if opts.size_bytes || opts.human_readable || opts.si_units || opts.du {
+        fields.push(("size", FieldValue::Num(meta.size)));
+    }
+    if opts.date {
+        let time = format_mtime(meta.modified);
+        fields.push(("time", FieldValue::Str(strip_brackets(&time))));
+    }
+    fields
+}
+
+fn strip_brackets(s: &str) -> String {
+    s.trim_matches(|c| c == '[' || c == ']').to_string()
+}
+
+/// GNU tree's element / `type` name for a node. A directory reached
+/// through a link followed with `-l` is still a link.
+pub(crate) fn type_name(node: &TreeNode) -> &'static str {
+    if node.link_target.is_some() {
+        return "link";
+    }
+    match node.kind {
+        NodeKind::Directory => "directory",
+        NodeKind::File => "file",
+        NodeKind::Symlink => "link",
+        NodeKind::Fifo => "fifo",
+        NodeKind::Socket => "socket",
+    }
+}
+
+/// Name for structured output: the full path with `-f`, else the raw name.
+/// The root is named by the path as given (`.` by default), like GNU tree.
+/// No quoting; callers escape.
+pub(crate) fn structured_name(node: &TreeNode, opts: &TreeConfig, is_root: bool) -> String {
+    if is_root {
+        opts.path.to_string_lossy().to_string()
+    } else if opts.full_path {
+        node.path.to_string_lossy().to_string()
+    } else {
+        node.name.clone()
+    }
+}
+
+/// Message GNU tree prints for a directory it could not list.
+pub(crate) fn error_message(err: &NodeError) -> String {
+    match err {
+        NodeError::ReadDir { message, .. } => format!("error opening dir: {}", message),
+        NodeError::FileLimit(n) => format!("{} entries exceeds filelimit, not opening dir", n),
+        NodeError::Recursive => "recursive, not followed".to_string(),
+    }
+}
+
+/// Escape markup characters for XML and HTML. Control characters other than
+/// tab, newline and carriage return are not allowed in XML 1.0 at all, so
+/// they become `?`.
+pub(crate) fn markup_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => {
+                let _ = write!(out, "&#{};", c as u32);
+            }
+            c if c.is_control() => out.push('?'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Path of `path` below `root` as a URL path, each component
+/// percent-encoded.
+pub(crate) fn relative_href(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    let encoded: Vec<String> = rel
+        .components()
+        .map(|c| percent_encode(&c.as_os_str().to_string_lossy()))
+        .collect();
+    encoded.join("/")
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn percent_encode(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for b in segment.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            let _ = write!(out, "%{:02X}", b);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_meta_prefix;
+    use super::json::render_json;
+    use super::xml::render_xml;
+    use crate::model::{NodeError, NodeKind, NodeMeta, TreeNode};
+    use crate::TreeConfig;
+
+    /// `/home/me/project` holding `a.txt`.
+    fn sample() -> TreeNode {
+        let mut root = TreeNode::new("/home/me/project", "/home/me/project", NodeKind::Directory);
+        root.children.push(TreeNode::new(
+            "a.txt",
+            "/home/me/project/a.txt",
+            NodeKind::File,
+        ));
+        root
+    }
+
+    /// `sample()` with a directory `name` reached through a link to
+    /// `target`, followed with `-l`.
+    fn with_link(name: &str, target: &str) -> TreeNode {
+        let mut link = TreeNode::new(
+            name,
+            format!("/home/me/project/{}", name),
+            NodeKind::Directory,
+        );
+        link.link_target = Some(target.into());
+        let mut root = sample();
+        root.children = vec![link];
+        root
+    }
+
+    fn opts(tc: TreeConfig) -> TreeConfig {
+        TreeConfig {
+            noreport: true,
+            ..tc
+        }
+    }
+
+    /// The JSON and the XML rendering.
+    fn render(root: &TreeNode, opts: &TreeConfig) -> (String, String) {
+        let (mut json, mut xml) = (String::new(), String::new());
+        render_json(&mut json, root, opts).unwrap();
+        render_xml(&mut xml, root, opts).unwrap();
+        (json, xml)
+    }
+
+    #[test]
+    fn root_is_named_by_the_path_as_given() {
+        let (json, xml) = render(&sample(), &opts(TreeConfig::default()));
+        assert_eq!(
+            json,
+            "[\n  {\"type\":\"directory\",\"name\":\".\",\"contents\":[\n    \
+             {\"type\":\"file\",\"name\":\"a.txt\"}\n  ]}\n]\n"
+        );
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tree>\n  \
+             <directory name=\".\">\n    <file name=\"a.txt\"></file>\n  \
+             </directory>\n</tree>\n"
+        );
+    }
+
+    #[test]
+    fn json_and_xml_agree() {
+        let recursive = {
+            let mut root = with_link("up", "..");
+            root.children[0].error = Some(NodeError::Recursive);
+            root
+        };
+        let followed = {
+            let mut root = with_link("docs", "../shared/docs");
+            root.children[0].children.push(TreeNode::new(
+                "a.md",
+                "/home/me/project/docs/a.md",
+                NodeKind::File,
+            ));
+            root
+        };
+        let follow = || TreeConfig {
+            follow_links: true,
+            ..TreeConfig::default()
+        };
+        // What, tree, options, then a piece of the JSON and of the XML.
+        let cases = [
+            (
+                "root path as given",
+                sample(),
+                TreeConfig {
+                    path: "../project/".into(),
+                    ..TreeConfig::default()
+                },
+                "{\"type\":\"directory\",\"name\":\"../project/\",",
+                "<directory name=\"../project/\">",
+            ),
+            (
+                "root path escaped",
+                sample(),
+                TreeConfig {
+                    path: "src & \"docs\"".into(),
+                    ..TreeConfig::default()
+                },
+                "\"name\":\"src & \\\"docs\\\"\",",
+                "<directory name=\"src &amp; &quot;docs&quot;\">",
+            ),
+            (
+                "-f names the entries, not the root",
+                sample(),
+                TreeConfig {
+                    full_path: true,
+                    ..TreeConfig::default()
+                },
+                "\"name\":\".\",\"contents\":[\n    \
+                 {\"type\":\"file\",\"name\":\"/home/me/project/a.txt\"}",
+                "<directory name=\".\">\n    <file name=\"/home/me/project/a.txt\">",
+            ),
+            (
+                "followed link",
+                followed,
+                follow(),
+                "{\"type\":\"link\",\"name\":\"docs\",\"target\":\"../shared/docs\",\
+                 \"contents\":[\n      {\"type\":\"file\",\"name\":\"a.md\"}\n    ]}",
+                "    <link name=\"docs\" target=\"../shared/docs\">\n      \
+                 <file name=\"a.md\"></file>\n    </link>\n",
+            ),
+            (
+                "link back to a parent",
+                recursive,
+                follow(),
+                "{\"type\":\"link\",\"name\":\"up\",\"target\":\"..\",\
+                 \"error\":\"recursive, not followed\",\"contents\":[]}",
+                "    <link name=\"up\" target=\"..\">\n      \
+                 <error>recursive, not followed</error>\n    </link>\n",
+            ),
+        ];
+        for (what, root, tc, json_part, xml_part) in cases {
+            let (json, xml) = render(&root, &opts(tc));
+            assert!(json.contains(json_part), "{}: JSON was\n{}", what, json);
+            assert!(xml.contains(xml_part), "{}: XML was\n{}", what, xml);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn owner_and_group_are_names_unless_numeric() {
+        let mut root = sample();
+        root.metadata = Some(NodeMeta {
+            uid: Some(0),
+            gid: Some(0),
+            ..NodeMeta::default()
+        });
+        let group = crate::meta::group_name(0);
+        let ids = |numeric_uid_gid| TreeConfig {
+            owner: true,
+            group: true,
+            numeric_uid_gid,
+            ..TreeConfig::default()
+        };
+        assert_eq!(
+            build_meta_prefix(&root, &ids(false)),
+            format!("root {} ", group)
+        );
+        assert_eq!(build_meta_prefix(&root, &ids(true)), "0 0 ");
+        let (json, xml) = render(&root, &opts(ids(true)));
+        assert!(json.contains("\"user\":\"0\",\"group\":\"0\""), "{}", json);
+        assert!(xml.contains("user=\"0\" group=\"0\""), "{}", xml);
+        let (json, _) = render(&root, &opts(ids(false)));
+        assert!(
+            json.contains(&format!("\"user\":\"root\",\"group\":\"{}\"", group)),
+            "{}",
+            json
+        );
+    }
+}
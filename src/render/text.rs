@@ -0,0 +1,234 @@
+// File: src\render\text.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: The classic tree2 output: connectors, emojis and ANSI colors.
+// License: MIT
+
+use std::fmt::{self, Write};
+
+use super::{build_meta_prefix, dir_meta_prefix, display_name, git_column, size_parts};
+use crate::color::*;
+use crate::meta::classify_indicator;
+use crate::model::{GitStatus, NodeError, NodeKind, TreeNode};
+use crate::TreeConfig;
+
+/// Render the whole tree: root line, entries and (unless `noreport`) the
+/// summary. With `colors` off the output is plain text.
+pub(crate) fn render_text(
+    out: &mut dyn Write,
+    root: &TreeNode,
+    opts: &TreeConfig,
+    colors: bool,
+) -> fmt::Result {
+    let root_text = format!("📂 {}/\n", root.name);
+    if colors {
+        write!(out, "{}{}{}", COLOR_BRIGHT_YELLOW, root_text, COLOR_RESET)?;
+    } else {
+        out.write_str(&root_text)?;
+    }
+
+    render_children(out, root, "", opts, colors)?;
+
+    if !opts.noreport {
+        let report = root.counts().report();
+        if colors {
+            write!(out, "{}{}{}", COLOR_BRIGHT_WHITE, report, COLOR_RESET)?;
+        } else {
+            out.write_str(&report)?;
+        }
+    }
+    Ok(())
+}
+
+fn render_children(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    prefix: &str,
+    opts: &TreeConfig,
+    colors: bool,
+) -> fmt::Result {
+    if let Some(NodeError::ReadDir { .. }) = node.error {
+        let txt = format!("{}└── 🔒 [Permission Denied]\n", prefix);
+        if colors {
+            write!(out, "{}{}{}", COLOR_WHITE_ON_RED, txt, COLOR_RESET)?;
+        } else {
+            out.write_str(&txt)?;
+        }
+        return Ok(());
+    }
+
+    for (idx, child) in node.children.iter().enumerate() {
+        let last = idx == node.children.len() - 1;
+        let connector = if last { "└── " } else { "├── " };
+
+        if child.ignored {
+            write_ignored_line(out, child, prefix, connector, opts, colors)?;
+        } else if child.is_dir() {
+            write_dir_line(out, child, prefix, connector, opts, colors)?;
+        } else {
+            write_file_line(out, child, prefix, connector, opts, colors)?;
+        }
+        if child.is_dir() {
+            let new_prefix = if last {
+                format!("{}    ", prefix)
+            } else {
+                format!("{}│   ", prefix)
+            };
+            render_children(out, child, &new_prefix, opts, colors)?;
+        }
+    }
+    Ok(())
+}
+
+/// An entry listed by `--show-ignored`: the plain line, dimmed and struck
+/// through, followed by an `[ignored]` tag.
+fn write_ignored_line(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    prefix: &str,
+    connector: &str,
+    opts: &TreeConfig,
+    colors: bool,
+) -> fmt::Result {
+    let mut line = String::new();
+    if node.is_dir() {
+        write_dir_line(&mut line, node, "", "", opts, false)?;
+    } else {
+        write_file_line(&mut line, node, "", "", opts, false)?;
+    }
+    let line = line.trim_end_matches('\n');
+    if colors {
+        writeln!(
+            out,
+            "{}{}{}{}{} {}[ignored]{}",
+            prefix, connector, COLOR_DIM_STRIKE, line, COLOR_RESET, COLOR_GRAY, COLOR_RESET
+        )
+    } else {
+        writeln!(out, "{}{}{} [ignored]", prefix, connector, line)
+    }
+}
+
+fn write_dir_line(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    prefix: &str,
+    connector: &str,
+    opts: &TreeConfig,
+    colors: bool,
+) -> fmt::Result {
+    let full = display_name(node, opts);
+    let meta_str = dir_meta_prefix(node, opts);
+    let indicator = if opts.classify { "/" } else { "" };
+
+    let git = git_column(node, opts);
+    // A directory reached through a link followed with -l.
+    let link_info = match node.link_target {
+        Some(ref target) => format!(" -> {}", target.display()),
+        None => String::new(),
+    };
+    let recursive = if node.error == Some(NodeError::Recursive) {
+        "  [recursive, not followed]"
+    } else {
+        ""
+    };
+
+    if colors {
+        write!(out, "{}{}", prefix, connector)?;
+        if !git.is_empty() {
+            write!(out, "{}{}{}", git_color(node.git), git, COLOR_RESET)?;
+        }
+        write!(
+            out,
+            "{}{}{}{}📁 {}{}/{}",
+            COLOR_GRAY, meta_str, COLOR_RESET, COLOR_BRIGHT_YELLOW, full, indicator, COLOR_RESET,
+        )?;
+        if !link_info.is_empty() {
+            write!(out, "{}{}{}", COLOR_BRIGHT_GREEN, link_info, COLOR_RESET)?;
+        }
+        if !recursive.is_empty() {
+            write!(out, "{}{}{}", COLOR_GRAY, recursive, COLOR_RESET)?;
+        }
+        out.write_str("\n")
+    } else {
+        writeln!(
+            out,
+            "{}{}{}{}📁 {}{}/{}{}",
+            prefix, connector, git, meta_str, full, indicator, link_info, recursive
+        )
+    }
+}
+
+fn write_file_line(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    prefix: &str,
+    connector: &str,
+    opts: &TreeConfig,
+    colors: bool,
+) -> fmt::Result {
+    let full = display_name(node, opts);
+    let is_symlink = node.kind == NodeKind::Symlink;
+    let meta_str = build_meta_prefix(node, opts);
+    let sz = node.size();
+
+    let indicator = if opts.classify {
+        classify_indicator(node.kind, node.metadata.as_ref())
+    } else {
+        ""
+    };
+
+    let (size_val, size_unit) = size_parts(sz, opts);
+
+    let symlink_info = match node.link_target {
+        Some(ref target) => format!(" -> {}", target.display()),
+        None => String::new(),
+    };
+
+    let git = git_column(node, opts);
+
+    if !colors {
+        return writeln!(
+            out,
+            "{}{}{}{}📄 {}{} ({} {}){}",
+            prefix, connector, git, meta_str, full, indicator, size_val, size_unit, symlink_info
+        );
+    }
+
+    let name_color = if is_symlink {
+        COLOR_BRIGHT_GREEN
+    } else {
+        COLOR_BRIGHT_CYAN
+    };
+    write!(out, "{}{}", prefix, connector)?;
+    if !git.is_empty() {
+        write!(out, "{}{}{}", git_color(node.git), git, COLOR_RESET)?;
+    }
+    if !meta_str.is_empty() {
+        write!(out, "{}{}{}", COLOR_GRAY, meta_str, COLOR_RESET)?;
+    }
+    write!(out, "{}📄 {}{}{}", name_color, full, indicator, COLOR_RESET)?;
+    if !symlink_info.is_empty() {
+        write!(out, "{}{}{}", COLOR_BRIGHT_GREEN, symlink_info, COLOR_RESET)?;
+    }
+    out.write_str(" (")?;
+    if sz == 0 {
+        write!(out, "{}{}", COLOR_WHITE_ON_RED, size_val)?;
+    } else {
+        write!(out, "{}{}", COLOR_LIGHT_MAGENTA_TRUE, size_val)?;
+    }
+    write!(out, "{} ", COLOR_RESET)?;
+    write!(out, "{}{}", COLOR_ORANGE, size_unit)?;
+    writeln!(out, "{})", COLOR_RESET)
+}
+
+/// Color of a `--git-status` marker.
+fn git_color(status: Option<GitStatus>) -> &'static str {
+    match status {
+        Some(GitStatus::Modified) | Some(GitStatus::Dirty) => COLOR_ORANGE,
+        Some(GitStatus::Added) => COLOR_BRIGHT_GREEN,
+        Some(GitStatus::Deleted) => COLOR_WHITE_ON_RED,
+        Some(GitStatus::Renamed) => COLOR_LIGHT_MAGENTA_TRUE,
+        Some(GitStatus::Untracked) => COLOR_RED,
+        Some(GitStatus::Ignored) | None => COLOR_GRAY,
+    }
+}
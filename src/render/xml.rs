@@ -0,0 +1,90 @@
+// File: src\render\xml.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: XML output laid out like GNU `tree -X`.
+// License: MIT
+
+use std::fmt::{self, Write};
+
+use super::{error_message, markup_escape, meta_fields, structured_name, type_name, FieldValue};
+use crate::model::TreeNode;
+use crate::TreeConfig;
+
+/// Render the tree as `<tree>` holding the root `<directory>` and, unless
+/// `noreport`, a `<report>` element.
+pub(crate) fn render_xml(out: &mut dyn Write, root: &TreeNode, opts: &TreeConfig) -> fmt::Result {
+    out.write_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tree>\n")?;
+    write_node(out, root, opts, 1)?;
+    if !opts.noreport {
+        let counts = root.counts();
+        out.write_str("  <report>\n")?;
+        if opts.du {
+            writeln!(out, "    <size>{}</size>", root.size())?;
+        }
+        writeln!(out, "    <directories>{}</directories>", counts.dirs)?;
+        writeln!(out, "    <files>{}</files>", counts.files)?;
+        if opts.show_ignored {
+            writeln!(
+                out,
+                "    <ignored_directories>{}</ignored_directories>",
+                counts.ignored_dirs
+            )?;
+            writeln!(
+                out,
+                "    <ignored_files>{}</ignored_files>",
+                counts.ignored_files
+            )?;
+        }
+        if opts.git_status {
+            writeln!(out, "    <deleted>{}</deleted>", counts.deleted)?;
+        }
+        out.write_str("  </report>\n")?;
+    }
+    out.write_str("</tree>\n")
+}
+
+fn write_node(
+    out: &mut dyn Write,
+    node: &TreeNode,
+    opts: &TreeConfig,
+    depth: usize,
+) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    let tag = type_name(node);
+    let name = structured_name(node, opts, depth == 1);
+    write!(out, "{}<{} name=\"{}\"", indent, tag, markup_escape(&name))?;
+    for (key, value) in meta_fields(node, opts) {
+        match value {
+            FieldValue::Num(n) => write!(out, " {}=\"{}\"", key, n)?,
+            FieldValue::Str(s) => write!(out, " {}=\"{}\"", key, markup_escape(&s))?,
+        }
+    }
+    if let Some(ref target) = node.link_target {
+        write!(
+            out,
+            " target=\"{}\"",
+            markup_escape(&target.to_string_lossy())
+        )?;
+    }
+    if node.ignored {
+        out.write_str(" ignored=\"true\"")?;
+    }
+    out.write_str(">")?;
+
+    if node.children.is_empty() && node.error.is_none() {
+        return writeln!(out, "</{}>", tag);
+    }
+    out.write_str("\n")?;
+    if let Some(ref err) = node.error {
+        writeln!(
+            out,
+            "{}  <error>{}</error>",
+            indent,
+            markup_escape(&error_message(err))
+        )?;
+    }
+    for child in &node.children {
+        write_node(out, child, opts, depth + 1)?;
+    }
+    writeln!(out, "{}</{}>", indent, tag)
+}
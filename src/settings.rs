@@ -0,0 +1,843 @@
+// File: src\settings.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Configuration files: the user config (~/.tree2/tree2.toml,
+//              .json, .yml, .ini, .env) and per-project .tree2.toml files,
+//              their lookup order, parsing and layering. See CONFIG.md.
+// License: MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::TreeConfig;
+
+/// Prefix of the keys tree2 reads from a `.env` file.
+const ENV_PREFIX: &str = "TREE2_";
+
+/// Name of the per-project config file.
+const PROJECT_FILE: &str = ".tree2.toml";
+
+/// Options read from configuration files. Keys left out of a file are
+/// `None`.
+///
+/// Every command line option has a key named after its long flag, with `_`
+/// for `-` (`dirs_only`, `sort_time`, `level`, ...). `default_excludes`,
+/// `ignore_files` and `exceptions` are the keys documented in CONFIG.md.
+///
+/// Named profiles live in `[profile.NAME]` tables holding the same keys plus
+/// `extends`, and are layered on top with [`Settings::with_profile`].
+/// An unknown key makes the file invalid, in every format.
+///
+/// ```no_run
+/// use tree2::{Settings, TreeConfig};
+///
+/// let mut config = TreeConfig::default();
+/// Settings::discover(&config.path).apply(&mut config);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    /// Names excluded by exact match, like `--exclude`.
+    pub exclude: Option<Vec<String>>,
+    /// Names or wildcards always excluded, in addition to `exclude`.
+    pub default_excludes: Option<Vec<String>>,
+    /// Ignore files to load (`-i`).
+    #[serde(alias = "ignore_file")]
+    pub ignore_files: Option<Vec<String>>,
+    /// Patterns that are never excluded (`--exception`).
+    #[serde(alias = "exception")]
+    pub exceptions: Option<Vec<String>>,
+    pub no_git_excludes: Option<bool>,
+    pub clipboard: Option<bool>,
+    pub all: Option<bool>,
+    pub show_ignored: Option<bool>,
+    pub git_status: Option<bool>,
+    pub git_tracked: Option<bool>,
+    pub git_untracked: Option<bool>,
+    pub rev: Option<String>,
+    pub threads: Option<usize>,
+    pub dirs_only: Option<bool>,
+    pub full_path: Option<bool>,
+    pub follow_links: Option<bool>,
+    pub level: Option<usize>,
+    pub pattern: Option<String>,
+    pub ignore_pattern: Option<String>,
+    pub ignore_case: Option<bool>,
+    pub dirsfirst: Option<bool>,
+    pub sort_time: Option<bool>,
+    pub sort_size: Option<bool>,
+    pub reverse: Option<bool>,
+    pub protections: Option<bool>,
+    pub owner: Option<bool>,
+    pub group: Option<bool>,
+    pub numeric_uid_gid: Option<bool>,
+    pub size: Option<bool>,
+    pub human_readable: Option<bool>,
+    pub si: Option<bool>,
+    pub date: Option<bool>,
+    pub classify: Option<bool>,
+    pub filelimit: Option<usize>,
+    pub prune: Option<bool>,
+    pub du: Option<bool>,
+    pub du_all: Option<bool>,
+    pub noreport: Option<bool>,
+    pub output: Option<String>,
+    /// Output format name as accepted by `--format`.
+    pub format: Option<String>,
+    pub json: Option<bool>,
+    pub xml: Option<bool>,
+    /// Base HREF for HTML output (`-H`).
+    pub html: Option<String>,
+    pub nocolor: Option<bool>,
+    pub quote_chars: Option<bool>,
+    pub quote: Option<bool>,
+    pub xdev: Option<bool>,
+    pub inodes: Option<bool>,
+    pub device: Option<bool>,
+    pub why: Option<bool>,
+    /// Profiles this one builds on, applied in order before its own keys.
+    /// Only meaningful inside a `[profile.NAME]` table.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub extends: Option<Vec<String>>,
+    /// Named profiles (`[profile.NAME]`).
+    #[serde(default)]
+    pub profile: BTreeMap<String, Settings>,
+}
+
+impl Settings {
+    /// User config file locations in the order CONFIG.md documents them.
+    pub fn search_paths() -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        #[cfg(windows)]
+        {
+            let profile = std::env::var_os("USERPROFILE").map(PathBuf::from);
+            let appdata = std::env::var_os("APPDATA").map(PathBuf::from);
+            if let Some(ref p) = profile {
+                paths.push(p.join(".tree2").join(".env"));
+            }
+            for name in ["tree2.toml", "tree2.json", "tree2.yml", "tree2.ini"] {
+                for base in [&appdata, &profile].into_iter().flatten() {
+                    paths.push(base.join(".tree2").join(name));
+                }
+            }
+        }
+        #[cfg(not(windows))]
+        {
+            if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+                let config = home.join(".config");
+                for name in [".env", "tree2.toml", "tree2.json", "tree2.yml", "tree2.ini"] {
+                    paths.push(home.join(".tree2").join(name));
+                    paths.push(config.join(".tree2").join(name));
+                    paths.push(config.join(name));
+                }
+            }
+        }
+        paths
+    }
+
+    /// Load the first config file found in [`Settings::search_paths`].
+    ///
+    /// A `.env` file only counts when it sets at least one `TREE2_*` key, so
+    /// a shared `~/.config/.env` does not shadow `tree2.toml`. A file that
+    /// fails to parse is reported on stderr and no config is used.
+    pub fn load_user() -> Option<(PathBuf, Settings)> {
+        for path in Settings::search_paths() {
+            if !path.is_file() {
+                continue;
+            }
+            match Settings::from_file(&path) {
+                Ok(Some(settings)) => return Some((path, settings)),
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("Warning: ignoring config '{}': {}", path.display(), e);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Every `.tree2.toml` in `target` and its ancestors, farthest first.
+    /// Files that fail to parse are reported on stderr and skipped, and so
+    /// are files that set `output` or `clipboard`: a checkout must not
+    /// decide where tree2 writes.
+    pub fn load_project(target: &Path) -> Vec<(PathBuf, Settings)> {
+        let mut found = Vec::new();
+        for dir in target.ancestors() {
+            let path = dir.join(PROJECT_FILE);
+            if !path.is_file() {
+                continue;
+            }
+            let loaded = Settings::from_file(&path).and_then(|settings| {
+                match settings.as_ref().and_then(Settings::user_only_key) {
+                    Some(key) => Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("`{}` can only be set in the user config", key),
+                    )),
+                    None => Ok(settings),
+                }
+            });
+            match loaded {
+                Ok(Some(settings)) => found.push((path, settings)),
+                Ok(None) => {}
+                Err(e) => eprintln!("Warning: ignoring config '{}': {}", path.display(), e),
+            }
+        }
+        found.reverse();
+        found
+    }
+
+    /// The first key set here or in a profile that only the user config
+    /// may set, because it writes outside the terminal.
+    fn user_only_key(&self) -> Option<&'static str> {
+        if self.output.is_some() {
+            return Some("output");
+        }
+        if self.clipboard.is_some() {
+            return Some("clipboard");
+        }
+        self.profile.values().find_map(Settings::user_only_key)
+    }
+
+    /// The user config with every project config for `target` layered on
+    /// top, nearest directory last.
+    pub fn discover(target: &Path) -> Settings {
+        let mut settings = Settings::load_user().map(|(_, s)| s).unwrap_or_default();
+        for (_, project) in Settings::load_project(target) {
+            settings.merge(project);
+        }
+        settings
+    }
+
+    /// Parse one config file, choosing the format from its name. Returns
+    /// `None` for a `.env` file without `TREE2_*` keys.
+    pub fn from_file(path: &Path) -> io::Result<Option<Settings>> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase());
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        if name.as_deref() == Some(".env") {
+            return parse_env(&content).map_err(invalid);
+        }
+        let settings = match ext.as_deref() {
+            Some("toml") => toml::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+            Some("json") => serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+            Some("yml") | Some("yaml") => {
+                // An empty YAML document is an empty config, not an error.
+                if content.trim().is_empty() {
+                    Settings::default()
+                } else {
+                    serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?
+                }
+            }
+            Some("ini") => parse_ini(&content).map_err(invalid)?,
+            _ => return Err(invalid("unknown config format".to_string())),
+        };
+        Ok(Some(settings))
+    }
+
+    /// Layer `over` on top of `self`. The exclude lists and `exceptions`
+    /// are appended; every other key, and every profile `over` defines, is
+    /// replaced.
+    pub fn merge(&mut self, over: Settings) {
+        macro_rules! append {
+            ($($key:ident),*) => {$(
+                if let Some(values) = over.$key {
+                    self.$key.get_or_insert_with(Vec::new).extend(values);
+                }
+            )*};
+        }
+        macro_rules! replace {
+            ($($key:ident),*) => {$(
+                if over.$key.is_some() {
+                    self.$key = over.$key;
+                }
+            )*};
+        }
+        append!(exclude, default_excludes, exceptions);
+        self.profile.extend(over.profile);
+        replace!(
+            ignore_files,
+            no_git_excludes,
+            clipboard,
+            all,
+            show_ignored,
+            git_status,
+            git_tracked,
+            git_untracked,
+            rev,
+            threads,
+            dirs_only,
+            full_path,
+            follow_links,
+            level,
+            pattern,
+            ignore_pattern,
+            ignore_case,
+            dirsfirst,
+            sort_time,
+            sort_size,
+            reverse,
+            protections,
+            owner,
+            group,
+            numeric_uid_gid,
+            size,
+            human_readable,
+            si,
+            date,
+            classify,
+            filelimit,
+            prune,
+            du,
+            du_all,
+            noreport,
+            output,
+            format,
+            json,
+            xml,
+            html,
+            nocolor,
+            quote_chars,
+            quote,
+            xdev,
+            inodes,
+            device,
+            why,
+            extends
+        );
+    }
+
+    /// These settings with the profile `name` layered on top. The profiles
+    /// it `extends` are resolved first, recursively.
+    pub fn with_profile(&self, name: &str) -> Result<Settings, String> {
+        let resolved = self.resolve_profile(name, &mut Vec::new())?;
+        let mut settings = self.clone();
+        settings.merge(resolved);
+        settings.extends = None;
+        Ok(settings)
+    }
+
+    fn resolve_profile(&self, name: &str, chain: &mut Vec<String>) -> Result<Settings, String> {
+        if chain.iter().any(|n| n == name) {
+            chain.push(name.to_string());
+            return Err(format!("profile cycle: {}", chain.join(" -> ")));
+        }
+        let profile = self.profile.get(name).ok_or_else(|| {
+            if self.profile.is_empty() {
+                format!("unknown profile '{}': no profiles are defined", name)
+            } else {
+                let names: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+                format!(
+                    "unknown profile '{}' (available: {})",
+                    name,
+                    names.join(", ")
+                )
+            }
+        })?;
+
+        chain.push(name.to_string());
+        let mut resolved = Settings::default();
+        for parent in profile.extends.iter().flatten() {
+            resolved.merge(self.resolve_profile(parent, chain)?);
+        }
+        chain.pop();
+
+        let mut own = profile.clone();
+        own.extends = None;
+        own.profile.clear();
+        resolved.merge(own);
+        Ok(resolved)
+    }
+
+    /// Set one key from its text form, as written in INI and `.env` files.
+    /// Lists are comma separated and appended to; `-` may stand for `_`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let key = key.trim().to_lowercase().replace('-', "_");
+        let value = unquote(value.trim());
+        macro_rules! keys {
+            (
+                lists: $($list:ident $(| $alias:literal)*),*;
+                flags: $($flag:ident),*;
+                numbers: $($num:ident),*;
+                strings: $($text:ident),*;
+            ) => {
+                match key.as_str() {
+                    $(stringify!($list) $(| $alias)* => {
+                        self.$list
+                            .get_or_insert_with(Vec::new)
+                            .extend(split_list(value).map(String::from));
+                    })*
+                    $(stringify!($flag) => self.$flag = Some(parse_bool(value)?),)*
+                    $(stringify!($num) => {
+                        let n = value
+                            .parse()
+                            .map_err(|_| format!("'{}' expects a number, got '{}'", key, value))?;
+                        self.$num = Some(n);
+                    })*
+                    $(stringify!($text) => self.$text = Some(value.to_string()),)*
+                    _ => return Err(format!("unknown key '{}'", key)),
+                }
+            };
+        }
+        keys! {
+            lists: exclude, default_excludes, extends, ignore_files | "ignore_file",
+                exceptions | "exception";
+            flags: no_git_excludes, clipboard, all, show_ignored, git_status, git_tracked,
+                git_untracked, dirs_only, full_path, follow_links, ignore_case, dirsfirst,
+                sort_time, sort_size, reverse, protections, owner, group, numeric_uid_gid, size,
+                human_readable, si, date, classify, prune, du, du_all, noreport, json, xml, nocolor,
+                quote_chars, quote, xdev, inodes, device, why;
+            numbers: level, filelimit, threads;
+            strings: rev, pattern, ignore_pattern, output, format, html;
+        }
+        Ok(())
+    }
+
+    /// Copy the keys that map onto [`TreeConfig`] into `config`. The exclude
+    /// lists are added to what `config` has; other keys replace it. Keys
+    /// that only concern the command line (`clipboard`, `output`, `format`,
+    /// `json`, `xml`, `html`) are left alone.
+    pub fn apply(&self, config: &mut TreeConfig) {
+        macro_rules! copy {
+            ($($key:ident => $field:ident),* $(,)?) => {$(
+                if let Some(ref v) = self.$key {
+                    config.$field = v.clone();
+                }
+            )*};
+        }
+        macro_rules! copy_opt {
+            ($($key:ident => $field:ident),* $(,)?) => {$(
+                if let Some(ref v) = self.$key {
+                    config.$field = Some(v.clone());
+                }
+            )*};
+        }
+        if let Some(ref names) = self.exclude {
+            config.excludes.extend(names.iter().cloned());
+        }
+        if let Some(ref patterns) = self.default_excludes {
+            config.default_excludes.extend(patterns.iter().cloned());
+        }
+        copy! {
+            ignore_files => ignore_files,
+            no_git_excludes => no_git_excludes,
+            exceptions => exceptions,
+            all => show_all,
+            show_ignored => show_ignored,
+            git_status => git_status,
+            git_tracked => git_tracked,
+            git_untracked => git_untracked,
+            dirs_only => dirs_only,
+            full_path => full_path,
+            follow_links => follow_links,
+            ignore_case => ignore_case,
+            dirsfirst => dirsfirst,
+            sort_time => sort_time,
+            sort_size => sort_size,
+            reverse => reverse,
+            protections => protections,
+            owner => owner,
+            group => group,
+            numeric_uid_gid => numeric_uid_gid,
+            size => size_bytes,
+            human_readable => human_readable,
+            si => si_units,
+            date => date,
+            classify => classify,
+            prune => prune,
+            du => du,
+            du_all => du_all,
+            noreport => noreport,
+            quote_chars => quote_chars,
+            quote => quote,
+            xdev => xdev,
+            inodes => inodes,
+            device => device,
+            why => why,
+            threads => threads,
+        }
+        copy_opt! {
+            rev => rev,
+            level => max_depth,
+            pattern => pattern,
+            ignore_pattern => ignore_pattern,
+            filelimit => filelimit,
+        }
+        if let Some(nocolor) = self.nocolor {
+            config.colors = !nocolor;
+        }
+    }
+}
+
+// ── INI / .env ────────────────────────────────────────────────────────────────
+
+enum IniSection {
+    /// Top of the file or `[tree2]`.
+    Main,
+    /// `[profile.NAME]`.
+    Profile(String),
+    /// Any other section; its keys are ignored.
+    Other,
+}
+
+/// `key = value` lines, outside any section or in `[tree2]`, plus
+/// `[profile.NAME]` sections.
+fn parse_ini(content: &str) -> Result<Settings, String> {
+    let mut settings = Settings::default();
+    let mut section = IniSection::Main;
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.trim();
+            section = if name.eq_ignore_ascii_case("tree2") {
+                IniSection::Main
+            } else if let Some(profile) = name.strip_prefix("profile.") {
+                IniSection::Profile(profile.trim().to_string())
+            } else {
+                IniSection::Other
+            };
+            continue;
+        }
+        let target = match section {
+            IniSection::Main => &mut settings,
+            IniSection::Profile(ref name) => settings.profile.entry(name.clone()).or_default(),
+            IniSection::Other => continue,
+        };
+        if let Some((key, value)) = line.split_once('=') {
+            target
+                .set(key, value)
+                .map_err(|e| format!("line {}: {}", idx + 1, e))?;
+        }
+    }
+    Ok(settings)
+}
+
+/// `TREE2_DEFAULT_EXCLUDES=a,b` style lines; keys without the prefix are
+/// ignored.
+fn parse_env(content: &str) -> Result<Option<Settings>, String> {
+    let mut settings = Settings::default();
+    let mut found = false;
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if let Some(key) = key.trim().strip_prefix(ENV_PREFIX) {
+            settings
+                .set(key, value)
+                .map_err(|e| format!("line {}: {}", idx + 1, e))?;
+            found = true;
+        }
+    }
+    Ok(found.then_some(settings))
+}
+
+/// Comma separated values, trimmed and unquoted; empty items are dropped.
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(|v| unquote(v.trim()))
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" | "" => Ok(false),
+        _ => Err(format!("expected true or false, got '{}'", value)),
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    for q in ['"', '\''] {
+        if s.len() >= 2 && s.starts_with(q) && s.ends_with(q) {
+            return &s[1..s.len() - 1];
+        }
+    }
+    s
+}
+
+/// `extends = "base"` or `extends = ["base", "extra"]`.
+fn one_or_many<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(Some(match OneOrMany::deserialize(d)? {
+        OneOrMany::One(name) => vec![name],
+        OneOrMany::Many(names) => names,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Option<Vec<String>> {
+        Some(items.iter().map(|s| s.to_string()).collect())
+    }
+
+    // ── Formats ──
+
+    #[test]
+    fn ini_reads_main_and_profile_sections() {
+        let settings = parse_ini(
+            "; comment\n\
+             level = 2\n\
+             [tree2]\n\
+             dirsfirst = yes\n\
+             exclude = \"target\", node_modules\n\
+             [profile.docs]\n\
+             pattern = *.md\n\
+             [other]\n\
+             whatever = 1\n",
+        )
+        .unwrap();
+        assert_eq!(settings.level, Some(2));
+        assert_eq!(settings.dirsfirst, Some(true));
+        assert_eq!(settings.exclude, strings(&["target", "node_modules"]));
+        assert_eq!(settings.profile["docs"].pattern.as_deref(), Some("*.md"));
+    }
+
+    #[test]
+    fn ini_rejects_unknown_keys_and_bad_values() {
+        assert_eq!(
+            parse_ini("dirs_first = true\n").unwrap_err(),
+            "line 1: unknown key 'dirs_first'"
+        );
+        assert_eq!(
+            parse_ini("\nlevel = two\n").unwrap_err(),
+            "line 2: 'level' expects a number, got 'two'"
+        );
+        assert!(parse_ini("prune = maybe\n").is_err());
+    }
+
+    #[test]
+    fn env_reads_prefixed_keys_only() {
+        let settings = parse_env(
+            "# comment\n\
+             PATH=/usr/bin\n\
+             export TREE2_DEFAULT_EXCLUDES=target,'*.log'\n\
+             TREE2_LEVEL=\"3\"\n\
+             TREE2_DU=1\n",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(settings.default_excludes, strings(&["target", "*.log"]));
+        assert_eq!(settings.level, Some(3));
+        assert_eq!(settings.du, Some(true));
+        assert_eq!(settings.prune, None);
+    }
+
+    #[test]
+    fn env_without_tree2_keys_is_no_config() {
+        assert_eq!(parse_env("PATH=/bin\nHOME=/root\n").unwrap(), None);
+    }
+
+    #[test]
+    fn env_rejects_unknown_keys() {
+        assert_eq!(
+            parse_env("TREE2_DIRS_FIRST=1\n").unwrap_err(),
+            "line 1: unknown key 'dirs_first'"
+        );
+    }
+
+    #[test]
+    fn toml_json_and_yaml_reject_unknown_keys() {
+        assert!(toml::from_str::<Settings>("dirsfirst = true").is_ok());
+        assert!(toml::from_str::<Settings>("dirs_first = true").is_err());
+        assert!(toml::from_str::<Settings>("[profile.x]\ndirs_first = true").is_err());
+        assert!(serde_json::from_str::<Settings>(r#"{"dirs_first": true}"#).is_err());
+        assert!(serde_yaml::from_str::<Settings>("dirs_first: true").is_err());
+    }
+
+    #[test]
+    fn aliases_are_accepted() {
+        let settings: Settings =
+            toml::from_str("ignore_file = [\".pt\"]\nexception = [\"*.md\"]").unwrap();
+        assert_eq!(settings.ignore_files, strings(&[".pt"]));
+        assert_eq!(settings.exceptions, strings(&["*.md"]));
+    }
+
+    // ── Layering ──
+
+    #[test]
+    fn merge_appends_lists_and_replaces_the_rest() {
+        let mut base: Settings = toml::from_str(
+            "exclude = [\"a\"]\nexceptions = [\"x\"]\nignore_files = [\".gitignore\"]\nlevel = 1\nprune = true",
+        )
+        .unwrap();
+        let over: Settings = toml::from_str(
+            "exclude = [\"b\"]\nexceptions = [\"y\"]\nignore_files = [\".pt\"]\nlevel = 3",
+        )
+        .unwrap();
+        base.merge(over);
+        assert_eq!(base.exclude, strings(&["a", "b"]));
+        assert_eq!(base.exceptions, strings(&["x", "y"]));
+        assert_eq!(base.ignore_files, strings(&[".pt"]));
+        assert_eq!(base.level, Some(3));
+        assert_eq!(base.prune, Some(true));
+    }
+
+    #[test]
+    fn merge_replaces_whole_profiles() {
+        let mut base: Settings =
+            toml::from_str("[profile.a]\nlevel = 1\nprune = true\n[profile.b]\nlevel = 2").unwrap();
+        let over: Settings = toml::from_str("[profile.a]\nlevel = 5").unwrap();
+        base.merge(over);
+        assert_eq!(base.profile["a"].level, Some(5));
+        assert_eq!(base.profile["a"].prune, None);
+        assert_eq!(base.profile["b"].level, Some(2));
+    }
+
+    // ── Project files ──
+
+    fn project(files: &[(&str, &str)]) -> (tempfile::TempDir, Vec<(PathBuf, Settings)>) {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("sub");
+        fs::create_dir(&target).unwrap();
+        for (at, content) in files {
+            fs::write(dir.path().join(at).join(PROJECT_FILE), content).unwrap();
+        }
+        let found = Settings::load_project(&target);
+        (dir, found)
+    }
+
+    #[test]
+    fn project_files_are_read_farthest_first() {
+        let (_dir, found) = project(&[("", "level = 1"), ("sub", "level = 2")]);
+        let levels: Vec<_> = found.iter().map(|(_, s)| s.level).collect();
+        assert_eq!(levels, [Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn project_files_cannot_set_output_or_clipboard() {
+        let (_dir, found) = project(&[
+            ("", "output = \"/etc/passwd\"\nlevel = 1"),
+            ("sub", "clipboard = true\nlevel = 2"),
+        ]);
+        assert!(found.is_empty());
+        let (_dir, found) = project(&[("sub", "[profile.p]\noutput = \"tree.txt\"")]);
+        assert!(found.is_empty());
+        let (_dir, found) = project(&[("", "level = 1"), ("sub", "clipboard = false")]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.level, Some(1));
+    }
+
+    #[test]
+    fn user_config_may_set_output_and_clipboard() {
+        let settings: Settings = toml::from_str("output = \"tree.txt\"\nclipboard = true").unwrap();
+        assert_eq!(settings.user_only_key(), Some("output"));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tree2.toml");
+        fs::write(&path, "output = \"tree.txt\"\nclipboard = true").unwrap();
+        let settings = Settings::from_file(&path).unwrap().unwrap();
+        assert_eq!(settings.output.as_deref(), Some("tree.txt"));
+        assert_eq!(settings.clipboard, Some(true));
+    }
+
+    // ── Profiles ──
+
+    fn profiles() -> Settings {
+        toml::from_str(
+            "level = 1\n\
+             exclude = [\"target\"]\n\
+             [profile.base]\n\
+             level = 2\n\
+             prune = true\n\
+             exclude = [\"dist\"]\n\
+             [profile.docs]\n\
+             extends = \"base\"\n\
+             pattern = \"*.md\"\n\
+             [profile.deep]\n\
+             extends = [\"docs\", \"sizes\"]\n\
+             level = 9\n\
+             [profile.sizes]\n\
+             du = true\n\
+             level = 4\n\
+             [profile.loop_a]\n\
+             extends = \"loop_b\"\n\
+             [profile.loop_b]\n\
+             extends = \"loop_a\"\n\
+             [profile.broken]\n\
+             extends = \"missing\"\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn profile_is_layered_on_top() {
+        let settings = profiles().with_profile("base").unwrap();
+        assert_eq!(settings.level, Some(2));
+        assert_eq!(settings.prune, Some(true));
+        assert_eq!(settings.exclude, strings(&["target", "dist"]));
+        assert_eq!(settings.extends, None);
+    }
+
+    #[test]
+    fn extends_chain_is_resolved_first() {
+        let settings = profiles().with_profile("docs").unwrap();
+        assert_eq!(settings.pattern.as_deref(), Some("*.md"));
+        assert_eq!(settings.level, Some(2));
+        assert_eq!(settings.prune, Some(true));
+    }
+
+    #[test]
+    fn several_parents_apply_in_order_then_own_keys() {
+        let settings = profiles().with_profile("deep").unwrap();
+        assert_eq!(settings.pattern.as_deref(), Some("*.md"));
+        assert_eq!(settings.du, Some(true));
+        assert_eq!(settings.prune, Some(true));
+        assert_eq!(settings.level, Some(9));
+    }
+
+    #[test]
+    fn later_parent_wins_over_earlier_one() {
+        let mut settings = profiles();
+        settings.profile.get_mut("deep").unwrap().level = None;
+        let settings = settings.with_profile("deep").unwrap();
+        assert_eq!(settings.level, Some(4));
+    }
+
+    #[test]
+    fn cycle_is_an_error() {
+        assert_eq!(
+            profiles().with_profile("loop_a").unwrap_err(),
+            "profile cycle: loop_a -> loop_b -> loop_a"
+        );
+    }
+
+    #[test]
+    fn unknown_profile_lists_the_available_ones() {
+        assert_eq!(
+            profiles().with_profile("nope").unwrap_err(),
+            "unknown profile 'nope' (available: base, broken, deep, docs, loop_a, loop_b, sizes)"
+        );
+        assert_eq!(
+            Settings::default().with_profile("nope").unwrap_err(),
+            "unknown profile 'nope': no profiles are defined"
+        );
+    }
+
+    #[test]
+    fn unknown_parent_is_an_error() {
+        assert!(profiles()
+            .with_profile("broken")
+            .unwrap_err()
+            .starts_with("unknown profile 'missing'"));
+    }
+}
@@ -0,0 +1,741 @@
+// File: src\walk.rs
+// Author: Hadi Cahyadi <cumulus13@gmail.com>
+// Date: 2026-10-17
+// Description: Filesystem traversal. Reads, filters and sorts directory entries
+//              into a TreeNode model; no output happens here.
+// License: MIT
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::gitstatus::{annotate, StatusMap};
+use crate::gittree::{disk_dir_meta, revision_entries, tracked_entries, untracked_entries};
+use crate::ignore::{
+    check_exclusion, load_nested_ignore_files, load_root_ignore_files, IgnoreStack,
+};
+use crate::meta::{accumulate_size, device_id, file_id};
+use crate::model::{Explanation, HideReason, NodeError, NodeKind, NodeMeta, TreeNode};
+use crate::pathtree::build_path_tree;
+use rayon::prelude::*;
+
+/// Build the tree rooted at `root` (already canonicalized). With `why` set
+/// in `config`, `hidden` receives an explanation for every entry left out
+/// or kept by an `--exception`. With `--rev`, `--git-tracked` or
+/// `--git-untracked` the entries come from git instead of the directory,
+/// which fails outside a work tree.
+pub(crate) fn build_tree(
+    root: &Path,
+    config: &Config,
+    hidden: &mut Vec<Explanation>,
+) -> io::Result<TreeNode> {
+    let mut node = TreeNode::new(root.display().to_string(), root, NodeKind::Directory);
+    node.metadata = disk_dir_meta(root);
+    if let Some(ref rev) = config.rev {
+        let (entries, dir_meta) = revision_entries(root, rev)?;
+        node.metadata = Some(dir_meta.clone());
+        build_path_tree(&mut node, entries, config, hidden, |_| {
+            Some(dir_meta.clone())
+        });
+    } else if config.git_tracked || config.git_untracked {
+        let entries = if config.git_tracked {
+            tracked_entries(root)?
+        } else {
+            untracked_entries(root)?
+        };
+        build_path_tree(&mut node, entries, config, hidden, disk_dir_meta);
+    } else {
+        let ignores = IgnoreStack::new(load_root_ignore_files(root, config));
+        let parents: Vec<(u64, u64)> = fs::metadata(root)
+            .ok()
+            .and_then(|m| file_id(&m))
+            .into_iter()
+            .collect();
+        let mut walk = || read_children(&mut node, config, &ignores, 1, &parents, hidden);
+        // Every directory is sorted on its own, so the tree comes out the
+        // same whichever thread read it.
+        let pool = (config.threads > 1).then(|| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(config.threads)
+                .build()
+        });
+        match pool {
+            Some(Ok(pool)) => pool.install(walk),
+            Some(Err(e)) => {
+                eprintln!("Warning: --threads: {}, reading serially", e);
+                walk();
+            }
+            None => walk(),
+        }
+    }
+    // The work tree status says nothing about another revision.
+    if config.git_status && config.rev.is_none() {
+        match StatusMap::load(root) {
+            Ok(status) => annotate(&mut node, &status, config),
+            Err(e) => eprintln!("Warning: --git-status: {}", e.message()),
+        }
+    }
+    if config.prune {
+        node.prune(1, config.level);
+    }
+    Ok(node)
+}
+
+/// Fill `node.children` with the filtered, sorted entries of its directory.
+/// `ignores` holds the ignore files of the directory and its ancestors;
+/// `depth` is the depth of the entries (children of the root are at 1).
+/// `parents` holds the `(device, inode)` of the directories on the way
+/// down, so that a followed link back to one of them is not descended.
+/// With `--threads`, entries are stat'ed and subdirectories read on the
+/// thread pool the walk runs in.
+///
+/// With `--du` the size of `node` is set from its children once they are
+/// read. Directories below `-L` or over `--filelimit` are then still read,
+/// for their size alone, and their children dropped again.
+fn read_children(
+    node: &mut TreeNode,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    depth: usize,
+    parents: &[(u64, u64)],
+    hidden: &mut Vec<Explanation>,
+) {
+    let listed = config.level.is_none_or(|max| depth <= max);
+    if !listed && !config.du {
+        return;
+    }
+
+    let entries: Vec<fs::DirEntry> = match fs::read_dir(&node.path) {
+        Ok(e) => e.collect::<Result<Vec<_>, _>>().unwrap_or_default(),
+        Err(e) => {
+            if listed {
+                node.error = Some(NodeError::read_dir(&e));
+            }
+            return;
+        }
+    };
+
+    if let Some(limit) = config.filelimit.filter(|_| listed) {
+        if entries.len() > limit {
+            node.error = Some(NodeError::FileLimit(entries.len()));
+            if config.why {
+                let reason = HideReason::FileLimit {
+                    entries: entries.len(),
+                    limit,
+                };
+                hidden.extend(entries.iter().map(|e| Explanation {
+                    path: config.relative(&e.path()),
+                    hidden_by: Some(reason.clone()),
+                    exception: None,
+                }));
+            }
+            if !config.du {
+                return;
+            }
+        }
+    }
+    // Verdicts are only of interest for entries that could have been listed.
+    let keep = listed && node.error.is_none();
+
+    let parallel = config.threads > 1;
+    let mut children = Vec::with_capacity(entries.len());
+    let mut skipped = 0;
+    for (child, verdicts, size) in map_items(&entries, parallel, |e| {
+        let mut verdicts = Vec::new();
+        let mut size = 0;
+        let child = entry_node(e, config, ignores, &mut verdicts, &mut size);
+        (child, verdicts, size)
+    }) {
+        children.extend(child);
+        skipped += size;
+        if keep {
+            hidden.extend(verdicts);
+        }
+    }
+
+    let ignored = node.ignored;
+    let below = map_items_mut(&mut children, parallel, |child| {
+        let mut verdicts = Vec::new();
+        child.ignored |= ignored;
+        if child.is_dir() {
+            descend(child, config, ignores, depth, parents, &mut verdicts);
+        }
+        verdicts
+    });
+    if keep {
+        hidden.extend(below.into_iter().flatten());
+    }
+    if config.du {
+        set_du_size(node, &children, skipped, config);
+    }
+    // Sorted once the sizes below are known, for --sort=size with --du.
+    if keep {
+        sort_nodes(&mut children, config);
+        node.children = children;
+    }
+}
+
+/// `--du`: the size of the directory `node` as the sum of its `children`,
+/// plus `skipped`, the size of what the filters hid from it (the files
+/// `-d` and `-P` leave out, everything with `--du-all`). Ignored entries shown by `--show-ignored` count
+/// towards an ignored directory alone, so that showing them does not
+/// change the totals.
+pub(crate) fn set_du_size(
+    node: &mut TreeNode,
+    children: &[TreeNode],
+    skipped: u64,
+    config: &Config,
+) {
+    let listed: u64 = children
+        .iter()
+        .filter(|c| config.du_all || node.ignored || !c.ignored)
+        .map(TreeNode::size)
+        .sum();
+    node.metadata.get_or_insert_with(NodeMeta::default).size = listed + skipped;
+}
+
+/// Read the directory `child` unless it is a link back to one of its
+/// `parents`.
+fn descend(
+    child: &mut TreeNode,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    depth: usize,
+    parents: &[(u64, u64)],
+    hidden: &mut Vec<Explanation>,
+) {
+    let id = child
+        .metadata
+        .as_ref()
+        .and_then(|m| Some((m.device?, m.inode?)));
+    if id.is_some_and(|id| parents.contains(&id)) {
+        child.error = Some(NodeError::Recursive);
+        return;
+    }
+    let nested = ignores.push(load_nested_ignore_files(&child.path, &config.ignore_names));
+    let mut chain = parents.to_vec();
+    chain.extend(id);
+    read_children(child, config, &nested, depth + 1, &chain, hidden);
+}
+
+/// `f` over `items`, in order, on the current thread pool when `parallel`.
+fn map_items<T: Sync, R: Send>(
+    items: &[T],
+    parallel: bool,
+    f: impl Fn(&T) -> R + Sync + Send,
+) -> Vec<R> {
+    if parallel {
+        items.par_iter().map(f).collect()
+    } else {
+        items.iter().map(f).collect()
+    }
+}
+
+fn map_items_mut<T: Send, R: Send>(
+    items: &mut [T],
+    parallel: bool,
+    f: impl Fn(&mut T) -> R + Sync + Send,
+) -> Vec<R> {
+    if parallel {
+        items.par_iter_mut().map(f).collect()
+    } else {
+        items.iter_mut().map(f).collect()
+    }
+}
+
+/// Apply the filters to one directory entry and turn it into a node. With
+/// `--show-ignored`, entries hidden only by an exclusion are kept and
+/// marked as ignored. With `--du-all`, the size of an entry the filters
+/// hide goes to `skipped`.
+fn entry_node(
+    entry: &fs::DirEntry,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    hidden: &mut Vec<Explanation>,
+    skipped: &mut u64,
+) -> Option<TreeNode> {
+    let name = entry.file_name().to_string_lossy().to_string();
+    let path = entry.path();
+    let mut meta = entry.metadata().ok()?;
+    let mut link_target = None;
+    if meta.file_type().is_symlink() {
+        link_target = fs::read_link(&path).ok();
+        // With -l a link to a directory is the directory, also for --xdev
+        // and the filters. Without inode numbers loops cannot be told
+        // apart, so such links stay links.
+        if config.follow_links {
+            if let Ok(target) = fs::metadata(&path) {
+                if target.is_dir() && file_id(&target).is_some() {
+                    meta = target;
+                }
+            }
+        }
+    }
+    let ignored = match admit(
+        &name,
+        &path,
+        meta.is_dir(),
+        device_id(&meta),
+        config,
+        ignores,
+        hidden,
+    ) {
+        Ok(ignored) => ignored,
+        Err(reason) => {
+            if config.du_all || (config.du && reason.is_listing_filter()) {
+                *skipped = if meta.is_dir() {
+                    accumulate_size(&path, config.root_dev.filter(|_| config.xdev))
+                } else {
+                    meta.len()
+                };
+            }
+            return None;
+        }
+    };
+
+    let mut node = TreeNode::new(name, &path, NodeKind::from_file_type(meta.file_type()));
+    node.ignored = ignored;
+    let mut node_meta = NodeMeta::from_metadata(&meta);
+    // Summed from the contents once they are read; a directory that is not
+    // read (a loop, no permission) has nothing to show.
+    if node.is_dir() && config.du {
+        node_meta.size = 0;
+    }
+    node.metadata = Some(node_meta);
+    node.link_target = link_target;
+    Some(node)
+}
+
+/// Whether an entry is listed: `Ok(false)` when it passes the filters,
+/// `Ok(true)` when only an exclusion hides it and `--show-ignored` keeps
+/// it as ignored, the reason when it is left out. With `--why`, the
+/// verdict on hidden and exception-kept entries goes to `hidden`.
+pub(crate) fn admit(
+    name: &str,
+    path: &Path,
+    is_dir: bool,
+    device: Option<u64>,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+    hidden: &mut Vec<Explanation>,
+) -> Result<bool, HideReason> {
+    let verdict = check(name, path, is_dir, device, config, ignores);
+    let ignored = match verdict.hidden_by {
+        None => false,
+        Some(ref reason)
+            if config.show_ignored
+                && reason.is_exclusion()
+                && filter_reason(name, is_dir, device, config).is_none() =>
+        {
+            true
+        }
+        Some(ref reason) => {
+            let reason = reason.clone();
+            if config.why {
+                hidden.push(verdict);
+            }
+            return Err(reason);
+        }
+    };
+    if config.why && (ignored || verdict.exception.is_some()) {
+        hidden.push(verdict);
+    }
+    Ok(ignored)
+}
+
+/// Run the entry filters in order: exclusions (with `--exception`),
+/// `--xdev`, `-I`, `-d` and `-P`. The first one that hides the entry
+/// decides.
+pub(crate) fn check_entry(
+    name: &str,
+    path: &Path,
+    meta: &fs::Metadata,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+) -> Explanation {
+    check(name, path, meta.is_dir(), device_id(meta), config, ignores)
+}
+
+fn check(
+    name: &str,
+    path: &Path,
+    is_dir: bool,
+    device: Option<u64>,
+    config: &Config,
+    ignores: &IgnoreStack<'_>,
+) -> Explanation {
+    let (hidden_by, exception) = match check_exclusion(name, path, is_dir, config, ignores) {
+        Ok(exception) => (filter_reason(name, is_dir, device, config), exception),
+        Err(reason) => (Some(reason), None),
+    };
+    Explanation {
+        path: config.relative(path),
+        hidden_by,
+        exception,
+    }
+}
+
+/// `device` is `None` for entries that are not on disk, which `--xdev`
+/// leaves alone.
+fn filter_reason(
+    name: &str,
+    is_dir: bool,
+    device: Option<u64>,
+    config: &Config,
+) -> Option<HideReason> {
+    if config.xdev {
+        if let (Some(root_dev), Some(dev)) = (config.root_dev, device) {
+            if dev != root_dev {
+                return Some(HideReason::OtherDevice);
+            }
+        }
+    }
+
+    if let Some(ref pat) = config.ignore_pattern {
+        if pat.matches(name) {
+            return Some(HideReason::IgnorePattern(pat.source().to_string()));
+        }
+    }
+
+    if config.dirs_only && !is_dir {
+        return Some(HideReason::DirsOnly);
+    }
+
+    if let Some(ref pat) = config.pattern {
+        if !is_dir && !pat.matches(name) {
+            return Some(HideReason::Pattern(pat.source().to_string()));
+        }
+    }
+    None
+}
+
+/// Name, time or size order, optionally reversed, then directories first.
+/// The largest entries come first by size, equal sizes by name.
+pub(crate) fn sort_nodes(nodes: &mut [TreeNode], config: &Config) {
+    if config.sort_time {
+        nodes.sort_by(|a, b| {
+            let ta = a.metadata.as_ref().and_then(|m| m.modified);
+            let tb = b.metadata.as_ref().and_then(|m| m.modified);
+            ta.cmp(&tb)
+        });
+    } else if config.sort_size {
+        nodes.sort_by(|a, b| b.size().cmp(&a.size()).then_with(|| a.name.cmp(&b.name)));
+    } else {
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    if config.reverse {
+        nodes.reverse();
+    }
+
+    if config.dirsfirst {
+        nodes.sort_by_key(|n| !n.is_dir());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TreeConfig;
+
+    /// `a/x.rs` (100 bytes), `a/b/y.txt` (200) and `z.txt` (50).
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a").join("b")).unwrap();
+        fs::write(dir.path().join("a").join("x.rs"), [b'x'; 100]).unwrap();
+        fs::write(dir.path().join("a").join("b").join("y.txt"), [b'y'; 200]).unwrap();
+        fs::write(dir.path().join("z.txt"), [b'z'; 50]).unwrap();
+        dir
+    }
+
+    fn build(dir: &Path, tc: TreeConfig) -> TreeNode {
+        let root = dunce::canonicalize(dir).unwrap();
+        let config = Config::resolve(&tc, &root);
+        build_tree(&root, &config, &mut Vec::new()).unwrap()
+    }
+
+    fn du(tc: TreeConfig) -> TreeConfig {
+        TreeConfig { du: true, ..tc }
+    }
+
+    /// The child of `node` at the `/`-separated `path`.
+    fn child<'a>(node: &'a TreeNode, path: &str) -> &'a TreeNode {
+        path.split('/').fold(node, |node, name| {
+            node.children
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("no {} in {}", name, node.name))
+        })
+    }
+
+    fn names(node: &TreeNode) -> Vec<&str> {
+        node.children.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    // ── --du ──
+
+    #[test]
+    fn du_sums_everything_listed_below() {
+        let dir = fixture();
+        let root = build(dir.path(), du(TreeConfig::default()));
+        assert_eq!(child(&root, "a/b").size(), 200);
+        assert_eq!(child(&root, "a").size(), 300);
+        assert_eq!(root.size(), 350);
+    }
+
+    #[test]
+    fn du_counts_the_files_dirs_only_leaves_out() {
+        let dir = fixture();
+        let root = build(
+            dir.path(),
+            du(TreeConfig {
+                dirs_only: true,
+                ..TreeConfig::default()
+            }),
+        );
+        assert_eq!(names(&root), ["a"]);
+        assert!(child(&root, "a/b").children.is_empty());
+        assert_eq!(child(&root, "a/b").size(), 200);
+        assert_eq!(child(&root, "a").size(), 300);
+    }
+
+    #[test]
+    fn du_counts_the_files_pattern_leaves_out() {
+        let dir = fixture();
+        let root = build(
+            dir.path(),
+            du(TreeConfig {
+                pattern: Some("*.rs".into()),
+                ..TreeConfig::default()
+            }),
+        );
+        assert_eq!(names(child(&root, "a")), ["b", "x.rs"]);
+        assert_eq!(child(&root, "a/b").size(), 200);
+        assert_eq!(child(&root, "a").size(), 300);
+    }
+
+    #[test]
+    fn du_counts_below_the_level() {
+        let dir = fixture();
+        let root = build(
+            dir.path(),
+            du(TreeConfig {
+                max_depth: Some(1),
+                dirs_only: true,
+                ..TreeConfig::default()
+            }),
+        );
+        assert!(child(&root, "a").children.is_empty());
+        assert_eq!(child(&root, "a").size(), 300);
+    }
+
+    #[test]
+    fn excluded_files_count_with_du_all_only() {
+        let dir = fixture();
+        let excluded = || TreeConfig {
+            excludes: vec!["y.txt".into()],
+            ..TreeConfig::default()
+        };
+        let root = build(dir.path(), du(excluded()));
+        assert_eq!(child(&root, "a").size(), 100);
+        let root = build(
+            dir.path(),
+            du(TreeConfig {
+                du_all: true,
+                ..excluded()
+            }),
+        );
+        assert!(child(&root, "a/b").children.is_empty());
+        assert_eq!(child(&root, "a").size(), 300);
+    }
+
+    #[test]
+    fn sort_size_puts_the_largest_first() {
+        let dir = fixture();
+        fs::write(dir.path().join("big.txt"), [b'b'; 250]).unwrap();
+        let by_size = || TreeConfig {
+            sort_size: true,
+            ..TreeConfig::default()
+        };
+        let root = build(dir.path(), du(by_size()));
+        assert_eq!(names(&root), ["a", "big.txt", "z.txt"]);
+        assert_eq!(names(child(&root, "a")), ["b", "x.rs"]);
+        let root = build(
+            dir.path(),
+            du(TreeConfig {
+                reverse: true,
+                ..by_size()
+            }),
+        );
+        assert_eq!(names(&root), ["z.txt", "big.txt", "a"]);
+    }
+
+    // ── --show-ignored ──
+
+    fn show_ignored() -> TreeConfig {
+        TreeConfig {
+            excludes: vec!["b".into()],
+            show_ignored: true,
+            ..TreeConfig::default()
+        }
+    }
+
+    #[test]
+    fn show_ignored_keeps_excluded_entries_marked() {
+        let dir = fixture();
+        let root = build(dir.path(), show_ignored());
+        assert_eq!(names(child(&root, "a")), ["b", "x.rs"]);
+        assert!(child(&root, "a/b").ignored);
+        // Everything inside an ignored directory is ignored too.
+        assert!(child(&root, "a/b/y.txt").ignored);
+        assert!(!child(&root, "a/x.rs").ignored);
+
+        let counts = root.counts();
+        assert_eq!((counts.dirs, counts.files), (1, 2));
+        assert_eq!((counts.ignored_dirs, counts.ignored_files), (1, 1));
+        assert_eq!(
+            counts.report(),
+            "\n1 directory, 2 files (1 directory, 1 file ignored)\n"
+        );
+    }
+
+    #[test]
+    fn show_ignored_leaves_the_system_folders_hidden() {
+        let dir = fixture();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".git").join("HEAD"), "ref").unwrap();
+        let root = build(dir.path(), show_ignored());
+        assert_eq!(names(&root), ["a", "z.txt"]);
+
+        let root = build(
+            dir.path(),
+            TreeConfig {
+                show_all: true,
+                ..show_ignored()
+            },
+        );
+        assert_eq!(names(&root), [".git", "a", "z.txt"]);
+        assert!(!child(&root, ".git").ignored);
+    }
+
+    #[test]
+    fn show_ignored_does_not_override_the_other_filters() {
+        let dir = fixture();
+        let root = build(
+            dir.path(),
+            TreeConfig {
+                pattern: Some("*.rs".into()),
+                ..show_ignored()
+            },
+        );
+        assert!(child(&root, "a/b").children.is_empty());
+    }
+
+    #[test]
+    fn ignored_entries_are_marked_in_json_and_xml() {
+        let dir = fixture();
+        let tc = TreeConfig {
+            path: dir.path().to_path_buf(),
+            ..show_ignored()
+        };
+        let tree = crate::TreeBuilder::from_config(tc).build().unwrap();
+        let json = tree.to_json();
+        assert!(json.contains("{\"type\":\"directory\",\"name\":\"b\",\"ignored\":true,"));
+        assert!(json.contains("{\"type\":\"file\",\"name\":\"x.rs\"}"));
+        assert!(json.contains(
+            "{\"type\":\"report\",\"directories\":1,\"files\":2,\
+             \"ignored_directories\":1,\"ignored_files\":1}"
+        ));
+        let xml = tree.to_xml();
+        assert!(xml.contains("<directory name=\"b\" ignored=\"true\">"));
+        assert!(xml.contains("<file name=\"y.txt\" ignored=\"true\"></file>"));
+        assert!(xml.contains("<ignored_directories>1</ignored_directories>"));
+        assert!(xml.contains("<ignored_files>1</ignored_files>"));
+    }
+
+    // ── --threads ──
+
+    /// A committed work tree of 8 × 8 directories with files of different
+    /// sizes, a few of them changed, deleted or added since.
+    fn wide_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut index = repo.index().unwrap();
+        for i in 0..8 {
+            for j in 0..8 {
+                let sub = dir.path().join(format!("d{}", i)).join(format!("e{}", j));
+                fs::create_dir_all(&sub).unwrap();
+                for k in 0..(i + j) % 5 + 1 {
+                    let name = format!("f{}.txt", k);
+                    fs::write(sub.join(&name), vec![b'x'; (i * 97 + j * 13 + k) % 300]).unwrap();
+                    index
+                        .add_path(Path::new(&format!("d{}/e{}/{}", i, j, name)))
+                        .unwrap();
+                }
+            }
+        }
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        fs::write(dir.path().join("d1/e2/f0.txt"), "changed").unwrap();
+        fs::remove_file(dir.path().join("d3/e4/f1.txt")).unwrap();
+        fs::write(dir.path().join("d5/e6/new.txt"), "new").unwrap();
+        dir
+    }
+
+    fn rendered(dir: &Path, tc: TreeConfig) -> String {
+        let tc = TreeConfig {
+            path: dir.to_path_buf(),
+            ..tc
+        };
+        let tree = crate::TreeBuilder::from_config(tc).build().unwrap();
+        tree.to_json() + &tree.to_string()
+    }
+
+    #[test]
+    fn threads_give_the_same_tree_as_a_serial_walk() {
+        let dir = wide_repo();
+        let configs = [
+            TreeConfig::default(),
+            TreeConfig {
+                sort_size: true,
+                du: true,
+                size_bytes: true,
+                ..TreeConfig::default()
+            },
+            TreeConfig {
+                du: true,
+                filelimit: Some(3),
+                max_depth: Some(2),
+                ..TreeConfig::default()
+            },
+            TreeConfig {
+                git_status: true,
+                dirsfirst: true,
+                reverse: true,
+                ..TreeConfig::default()
+            },
+        ];
+        for tc in configs {
+            let serial = rendered(
+                dir.path(),
+                TreeConfig {
+                    threads: 1,
+                    ..tc.clone()
+                },
+            );
+            for threads in [2, 8] {
+                let parallel = rendered(
+                    dir.path(),
+                    TreeConfig {
+                        threads,
+                        ..tc.clone()
+                    },
+                );
+                assert_eq!(parallel, serial, "{} threads", threads);
+            }
+        }
+    }
+}